@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::fs::File;
+use ron::de::from_reader;
+use serde::Deserialize;
+use crate::prelude::*;
+
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_startup_system_to_stage(StartupStage::PreStartup, Self::load)
+            .add_event::<AnimationFinished>()
+            .add_system(animate);
+    }
+}
+
+impl SpriteAnimationPlugin {
+    fn load(mut commands: Commands) {
+        commands.insert_resource(AnimationLibrary::load());
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    Once,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct SpriteClip {
+    pub frames: Vec<usize>,
+    pub fps: f32,
+    #[serde(default)]
+    pub mode: PlayMode,
+    /// State a `Once` clip hands over to, it holds its last frame without one.
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// The states of one sheet, each playing its clip.
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct AnimationSet(pub HashMap<String, SpriteClip>);
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct AnimationLibrary {
+    pub sets: HashMap<String, AnimationSet>,
+}
+
+impl AnimationLibrary {
+    pub fn load() -> Self {
+        let file = File::open("assets/Animations.ron").expect("Failed opening file");
+        from_reader(file).unwrap_or_else(|e| panic!("Unable to load animations: {}", e))
+    }
+
+    pub fn clip(&self, set: &str, state: &str) -> Option<&SpriteClip> {
+        self.sets.get(set).and_then(|s| s.0.get(state))
+    }
+
+    /// Clips that would stall or break a state machine, one line each.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (set_name, set) in self.sets.iter() {
+            for (state, clip) in set.0.iter() {
+                let mut error = |field: &str| errors.push(format!("Animation {}/{}: {}", set_name, state, field));
+
+                if clip.frames.is_empty() {
+                    error("no frames");
+                }
+                if clip.fps <= 0. {
+                    error("fps must be above 0");
+                }
+                if let Some(next) = &clip.next {
+                    if clip.mode == PlayMode::Loop {
+                        error("a looping clip never reaches its next state");
+                    }
+                    if !set.0.contains_key(next) {
+                        error(&format!("next state \"{}\" is not in the set", next));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Plays the clip of its current state from an `AnimationSet`.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Animator {
+    pub set: String,
+    pub state: String,
+    pub elapsed: f32,
+    pub finished: bool,
+}
+
+impl Animator {
+    pub fn new(set: &str, state: &str) -> Self {
+        Self {
+            set: set.to_string(),
+            state: state.to_string(),
+            ..default()
+        }
+    }
+
+    /// Switches state and starts its clip over, even when it is the current one.
+    pub fn play(&mut self, state: &str) {
+        self.state = state.to_string();
+        self.elapsed = 0.;
+        self.finished = false;
+    }
+}
+
+/// Sent once when a `Once` clip plays its last frame out.
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub state: String,
+}
+
+pub fn animate(
+    time: Res<GameClock>,
+    library: Res<AnimationLibrary>,
+    mut finished_event: EventWriter<AnimationFinished>,
+    mut animator_query: Query<(Entity, &mut Animator, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut animator, mut sprite) in animator_query.iter_mut() {
+        if animator.finished {
+            continue;
+        }
+        let clip = match library.clip(&animator.set, &animator.state) {
+            Some(clip) => clip,
+            None => continue,
+        };
+
+        animator.elapsed += time.delta().as_secs_f32();
+        let frame = (animator.elapsed * clip.fps) as usize;
+
+        if clip.mode == PlayMode::Once && frame >= clip.frames.len() {
+            finished_event.send(AnimationFinished { entity, state: animator.state.clone() });
+            match &clip.next {
+                Some(next) => animator.play(next),
+                None => {
+                    animator.finished = true;
+                    sprite.index = *clip.frames.last().unwrap();
+                    continue;
+                }
+            }
+        }
+
+        if let Some(clip) = library.clip(&animator.set, &animator.state) {
+            let frame = (animator.elapsed * clip.fps) as usize % clip.frames.len();
+            sprite.index = clip.frames[frame];
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use sbs_strategy::prelude::*;
+
+const USAGE: &str = "usage: mapgen [--seed S] [--name N]";
+
+fn main() {
+    let mut seed = 0;
+    let mut name = None;
+
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    for pair in args.chunks(2) {
+        let value = pair.get(1).unwrap_or_else(|| panic!("Missing value for {}\n{}", pair[0], USAGE));
+        match pair[0].as_str() {
+            "--seed" => seed = value.parse().expect(USAGE),
+            "--name" => name = Some(value.clone()),
+            _ => panic!("Unknown argument {}\n{}", pair[0], USAGE),
+        }
+    }
+
+    let map = MapGenerator::load().generate(seed);
+    let errors = Templates::load().storage().validate_map(&map);
+    for error in errors.iter() {
+        println!("{}", error);
+    }
+
+    match name {
+        Some(name) => match map.write(&name) {
+            Ok(_) => println!("map of seed {} saved to assets/map/{}.ron", seed, name),
+            Err(e) => println!("failed to save map: {}", e),
+        },
+        None => map.rows.iter().for_each(|row| println!("{}", row)),
+    }
+}
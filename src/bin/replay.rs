@@ -0,0 +1,41 @@
+use bevy::log::LogPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::winit::WinitPlugin;
+use sbs_strategy::prelude::*;
+
+const USAGE: &str = "usage: replay NAME [NAME ...]";
+
+/// Plays the replay back without a window or renderer, true when the end state matches.
+fn play(name: &str) -> bool {
+    let mut app = App::new();
+    app
+        .insert_resource(WgpuSettings {
+            backends: None,
+            ..default()
+        })
+        .insert_resource(Replay::playback(name))
+        .add_state(Menu)
+        // the log subscriber is global, a second replay in the same process can't set it again
+        .add_plugins_with(DefaultPlugins, |group| group.disable::<WinitPlugin>().disable::<LogPlugin>())
+        .add_plugins(GamePlugins);
+
+    // without winit nothing runs the schedule, frames are stepped until playback ends
+    while app.world.resource::<Replay>().matched.is_none() {
+        app.update();
+    }
+
+    app.world.resource::<Replay>().matched == Some(true)
+}
+
+fn main() {
+    let names = std::env::args().skip(1).collect::<Vec<String>>();
+    if names.is_empty() {
+        panic!("{}", USAGE);
+    }
+
+    let failed = names.iter().filter(|name| !play(name)).count();
+    if failed > 0 {
+        println!("{} of {} replays differ", failed, names.len());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,54 @@
+use sbs_strategy::prelude::*;
+
+const USAGE: &str = "usage: simulate [--fights N] [--seed S] [--days D] [--deck 1,5] [--items 24,25]";
+
+fn parse_ids(value: &str) -> Vec<usize> {
+    value
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(|id| id.trim().parse().unwrap_or_else(|_| panic!("Bad template id {}\n{}", id, USAGE)))
+        .collect()
+}
+
+fn main() {
+    let mut fights = 1000;
+    let mut seed = 0;
+    let mut setup = SimulationSetup {
+        deck: vec![1, 5],
+        items: vec![],
+        days: 0,
+    };
+
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    for pair in args.chunks(2) {
+        let value = pair.get(1).unwrap_or_else(|| panic!("Missing value for {}\n{}", pair[0], USAGE));
+        match pair[0].as_str() {
+            "--fights" => fights = value.parse().expect(USAGE),
+            "--seed" => seed = value.parse().expect(USAGE),
+            "--days" => setup.days = value.parse().expect(USAGE),
+            "--deck" => setup.deck = parse_ids(value),
+            "--items" => setup.items = parse_ids(value),
+            _ => panic!("Unknown argument {}\n{}", pair[0], USAGE),
+        }
+    }
+
+    let storage = Templates::load().storage();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    println!(
+        "deck {:?}, items {:?}, day {}, {} fights per enemy, seed {}",
+        setup.deck, setup.items, setup.days, fights, seed
+    );
+    println!("{:<14} {:>9} {:>11} {:>9}", "enemy", "win rate", "avg rounds", "avg hp");
+
+    for enemy in storage.enemies.iter() {
+        let report = simulate(&storage, &setup, enemy, fights, &mut rng);
+        println!(
+            "{:<14} {:>8.1}% {:>11.2} {:>9.2}",
+            report.enemy,
+            report.win_rate() * 100.,
+            report.average_rounds(),
+            report.average_hp_left(),
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use sbs_strategy::prelude::*;
+
+fn main() {
+    let templates = Templates::load();
+    let mut settings = MapSettings::default();
+    let library = AnimationLibrary::load();
+    let mut errors = templates.validate();
+    errors.extend(library.validate());
+    errors.extend(templates.validate_animations(&library));
+    let map = settings.map_file(GameRng::from_env().seed);
+    errors.extend(templates.storage().validate_map(&map));
+
+    for error in errors.iter() {
+        println!("{}", error);
+    }
+
+    if errors.is_empty() {
+        println!("{} templates and map {} are valid", templates.entities.len(), settings.name);
+    } else {
+        println!("{} errors", errors.len());
+        std::process::exit(1);
+    }
+}
@@ -62,7 +62,11 @@ fn camera_motion(
     keys: Res<Input<KeyCode>>,
 ) {
     let mut camera_transform = camera_query.single_mut();
-    let window = windows.get_primary().unwrap();
+    // headless replays run without a window
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
 
     let can_move_top_y  = camera_transform.translation.y < 4.;
     let can_move_bot_y = camera_transform.translation.y > -20.;
@@ -0,0 +1,129 @@
+use crate::combat::*;
+use crate::prelude::*;
+
+pub fn choose_ability<'a, R: Rng>(
+    abilities: &'a [EnemyAbility],
+    cooldowns: &mut [usize],
+    policy: AbilityPolicy,
+    stats: &CombatStats,
+    rng: &mut R,
+) -> Option<&'a EnemyAbility> {
+    let health_percent = if stats.max_health > 0 {
+        (stats.health * 100 / stats.max_health) as usize
+    } else {
+        0
+    };
+
+    let available = abilities
+        .iter()
+        .enumerate()
+        .filter(|(i, a)| cooldowns[*i] == 0 && a.hp_below.is_none_or(|hp| health_percent < hp))
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+
+    let chosen = match policy {
+        AbilityPolicy::WeightedRandom => weighted_pick(abilities, &available, rng),
+        AbilityPolicy::HpThreshold => {
+            match available.iter().find(|i| abilities[**i].hp_below.is_some()) {
+                Some(index) => Some(*index),
+                None => weighted_pick(abilities, &available, rng),
+            }
+        }
+    };
+
+    cooldowns.iter_mut().for_each(|turns| *turns = turns.saturating_sub(1));
+    chosen.map(|index| {
+        cooldowns[index] = abilities[index].cooldown;
+        &abilities[index]
+    })
+}
+
+fn weighted_pick<R: Rng>(
+    abilities: &[EnemyAbility],
+    available: &[usize],
+    rng: &mut R,
+) -> Option<usize> {
+    available
+        .choose_weighted(rng, |i| abilities[*i].weight)
+        .ok()
+        .copied()
+}
+
+pub fn resolve_ability(
+    ability: &EnemyAbility,
+    enemy_stats: &mut CombatStats,
+    manager: &mut CombatManager,
+    statuses: &mut StatusEffects,
+    player_statuses: &mut StatusEffects,
+) -> (isize, usize) {
+    match ability.action {
+        EnemyAction::Attack => (enemy_stats.attack, 1),
+        EnemyAction::MultiHit(hits) => (enemy_stats.attack, hits),
+        EnemyAction::Buff(value) => {
+            enemy_stats.attack += value;
+            (0, 1)
+        }
+        EnemyAction::Heal(value) => {
+            enemy_stats.health = std::cmp::min(enemy_stats.health + value, enemy_stats.max_health);
+            (0, 1)
+        }
+        EnemyAction::DrainMana(value) => {
+            manager.mana_drain += value;
+            (0, 1)
+        }
+        EnemyAction::SkipPlayerTurn => {
+            manager.player_skip_round = true;
+            (0, 1)
+        }
+        EnemyAction::Status(effect) => {
+            if effect.kind.is_harmful() {
+                player_statuses.apply(&effect);
+            } else {
+                statuses.apply(&effect);
+            }
+            (0, 1)
+        }
+    }
+}
+
+pub fn enemy_turn(
+    mut fight_event: EventWriter<FightEvent>,
+    mut enemy_query: Query<(&mut Enemy, &mut CombatStats, &mut StatusEffects), Without<Player>>,
+    mut player_query: Query<(Entity, &mut StatusEffects), With<Player>>,
+    mut manager: ResMut<CombatManager>,
+    mut rng: ResMut<GameRng>,
+) {
+    let (player_ent, mut player_statuses) = player_query.single_mut();
+    let acting_enemy = manager.acting_enemy;
+    let (mut enemy, mut enemy_stats, mut statuses) = match enemy_query
+        .iter_mut()
+        .filter(|(enemy, stats, ..)| stats.is_alive() && enemy.slot >= acting_enemy)
+        .min_by_key(|(enemy, ..)| enemy.slot)
+    {
+        Some(enemy) => enemy,
+        None => return,
+    };
+
+    manager.acting_enemy = enemy.slot;
+    let enemy = &mut *enemy;
+
+    let (damage_amount, hits) = if statuses.stunned {
+        println!("enemy is stunned");
+        (0, 1)
+    } else {
+        match choose_ability(&enemy.abilities, &mut enemy.cooldowns, enemy.policy, &enemy_stats, &mut rng.combat) {
+            Some(ability) => {
+                println!("enemy uses {}", ability.name);
+                resolve_ability(ability, &mut enemy_stats, &mut manager, &mut statuses, &mut player_statuses)
+            }
+            None => (enemy_stats.attack, 1),
+        }
+    };
+
+    fight_event.send(FightEvent {
+        target: player_ent,
+        damage_amount,
+        hits,
+        next_state: EnemyAttack,
+    });
+}
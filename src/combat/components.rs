@@ -1,7 +1,11 @@
-use crate::EnemyType::*;
+use serde::{Deserialize, Serialize};
 use crate::prelude::*;
 
-#[derive(Component, Inspectable)]
+pub const MAX_ENCOUNTER_ENEMIES: usize = 4;
+pub const ENEMY_SLOT_WIDTH: f32 = 4.;
+pub const HAND_SIZE: usize = 4;
+
+#[derive(Component, Inspectable, Clone, Serialize, Deserialize)]
 pub struct CombatStats {
     pub health: isize,
     pub attack: isize,
@@ -10,11 +14,51 @@ pub struct CombatStats {
     pub mana: isize,
 }
 
+impl CombatStats {
+    pub fn new_player() -> Self {
+        Self {
+            health: 10,
+            defense: 0,
+            attack: 0,
+            max_health: 10,
+            mana: 0,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+}
+
+impl Default for CombatStats {
+    fn default() -> Self {
+        Self::new_player()
+    }
+}
+
 #[derive(Component, Inspectable)]
 pub struct Enemy {
     pub enemy_type: EnemyType,
+    pub slot: usize,
+    #[inspectable(ignore)]
+    pub abilities: Vec<EnemyAbility>,
+    #[inspectable(ignore)]
+    pub policy: AbilityPolicy,
+    /// Turns left before each ability, by index, can be used again.
+    #[inspectable(ignore)]
+    pub cooldowns: Vec<usize>,
+}
+
+impl Enemy {
+    /// Horizontal shift of an enemy and its stat panel, keeping the group centered.
+    pub fn slot_offset(slot: usize, count: usize) -> f32 {
+        (slot as f32 - (count as f32 - 1.) / 2.) * ENEMY_SLOT_WIDTH
+    }
 }
 
+#[derive(Component)]
+pub struct Target;
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct CombatManager {
@@ -29,10 +73,38 @@ pub struct CombatManager {
     pub can_roll_attack: bool,
     pub skip_round: bool,
     pub enemy_skip_round: bool,
+    pub player_skip_round: bool,
+    pub mana_drain: isize,
     pub timer: Timer,
     pub enemy_death: bool,
     pub player_death: bool,
     pub enemy_lvl: usize,
+    pub acting_enemy: usize,
+    /// Max health gained by cards this fight, kept as a permanent reward.
+    pub max_health_gain: isize,
+    /// Dice rolled along with the attack and mana die, from talents.
+    pub extra_attack_dice: usize,
+    pub extra_mana_dice: usize,
+    #[reflect(ignore)]
+    pub timed_buffs: Vec<TimedBuff>,
+}
+
+/// Damage, defense or mana buff that holds for a number of rounds, the current one included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimedBuff {
+    pub kind: EffectKind,
+    pub value: isize,
+    pub rounds_left: usize,
+}
+
+impl TimedBuff {
+    pub fn icon_index(&self) -> usize {
+        match self.kind {
+            EffectKind::Defense => 5,
+            EffectKind::Mana => 3,
+            _ => 2,
+        }
+    }
 }
 
 impl CombatManager {
@@ -45,8 +117,142 @@ impl CombatManager {
         }
     }
 
-    pub fn is_range_buff(card: &Card) -> bool {
-        card.rounds == 0
+    pub fn cast(
+        &mut self,
+        mana_cost: usize,
+        is_used: bool,
+        effects: &[CardEffect],
+        stats: &mut CombatStats,
+        statuses: &mut StatusEffects,
+        mut target_statuses: Option<&mut StatusEffects>,
+    ) -> bool {
+        if self.skip_round || !self.if_can_cast(mana_cost, is_used) {
+            return false;
+        }
+
+        for effect in effects.iter() {
+            self.apply_effect(effect, stats, statuses, target_statuses.as_deref_mut());
+        }
+        true
+    }
+
+    pub fn init(&mut self, player_stats: &CombatStats) {
+        self.permanent_damage_buff = player_stats.attack;
+        self.permanent_defense_buff = player_stats.defense;
+        self.defense = self.permanent_defense_buff;
+        self.damage = player_stats.attack;
+        self.permanent_mana_buff = player_stats.mana;
+        self.mana_poll = self.permanent_mana_buff;
+    }
+
+    pub fn roll_attack(&mut self, roll: isize) {
+        self.damage += roll;
+        self.can_roll_attack = false;
+    }
+
+    pub fn roll_mana(&mut self, roll: isize) {
+        self.mana_poll += roll;
+        self.can_roll_mana = false;
+    }
+
+    pub fn next_round(&mut self) {
+        let stunned = self.player_skip_round;
+
+        self.damage = self.permanent_damage_buff + self.timed_buff(EffectKind::Damage);
+        self.mana_poll = std::cmp::max(
+            self.permanent_mana_buff + self.timed_buff(EffectKind::Mana) - self.mana_drain,
+            0,
+        );
+        self.defense = self.permanent_defense_buff + self.timed_buff(EffectKind::Defense);
+        self.can_roll_attack = !stunned;
+        self.can_roll_mana = !stunned;
+        self.round += 1;
+        self.skip_round = stunned;
+        self.enemy_skip_round = false;
+        self.player_skip_round = false;
+        self.mana_drain = 0;
+        self.acting_enemy = 0;
+    }
+
+    pub fn is_range_buff(rounds: usize) -> bool {
+        rounds == 0
+    }
+
+    fn timed_buff(&self, kind: EffectKind) -> isize {
+        self.timed_buffs
+            .iter()
+            .filter(|b| b.kind == kind)
+            .map(|b| b.value)
+            .sum()
+    }
+
+    fn add_timed_buff(&mut self, kind: EffectKind, value: isize, rounds: usize) {
+        self.timed_buffs.push(TimedBuff { kind, value, rounds_left: rounds });
+    }
+
+    /// Counts the finished round off every timed buff and drops the ones that ran out.
+    pub fn expire_buffs(&mut self) {
+        for buff in self.timed_buffs.iter_mut() {
+            buff.rounds_left -= 1;
+        }
+        self.timed_buffs.retain(|b| b.rounds_left > 0);
+    }
+
+    pub fn apply_effect(
+        &mut self,
+        effect: &CardEffect,
+        stats: &mut CombatStats,
+        statuses: &mut StatusEffects,
+        target_statuses: Option<&mut StatusEffects>,
+    ) {
+        let value = match effect.value {
+            EffectValue::Fixed(value) => value,
+            EffectValue::Round => self.round as isize,
+        };
+        let permanent = CombatManager::is_range_buff(effect.rounds);
+
+        match effect.kind {
+            EffectKind::SkipEnemyTurn => self.enemy_skip_round = true,
+            EffectKind::Damage => {
+                if permanent {
+                    self.permanent_damage_buff += value;
+                } else {
+                    self.damage += value;
+                    self.add_timed_buff(effect.kind, value, effect.rounds);
+                }
+            }
+            EffectKind::SetDamage => self.permanent_damage_buff = value,
+            EffectKind::Defense => {
+                if permanent {
+                    self.permanent_defense_buff += value;
+                } else {
+                    self.defense += value;
+                    self.add_timed_buff(effect.kind, value, effect.rounds);
+                }
+            }
+            EffectKind::Mana => {
+                if permanent {
+                    self.permanent_mana_buff += value;
+                } else {
+                    self.mana_poll += value;
+                    self.add_timed_buff(effect.kind, value, effect.rounds);
+                }
+            }
+            EffectKind::Heal => {
+                stats.health = std::cmp::min(stats.health + value, stats.max_health);
+            }
+            EffectKind::MaxHealth => {
+                stats.max_health += value;
+                self.max_health_gain += value;
+            }
+            EffectKind::Status(kind) => {
+                if !kind.is_harmful() {
+                    statuses.add(kind, value, effect.rounds);
+                } else if let Some(target_statuses) = target_statuses {
+                    target_statuses.add(kind, value, effect.rounds);
+                }
+            }
+        }
     }
 
     pub fn print(&self) {
@@ -71,10 +277,17 @@ impl Default for CombatManager {
             can_roll_attack: true,
             skip_round: false,
             enemy_skip_round: false,
+            player_skip_round: false,
+            mana_drain: 0,
             timer: Timer::from_seconds(2., true),
             enemy_death: false,
             player_death: false,
             enemy_lvl: 0,
+            acting_enemy: 0,
+            max_health_gain: 0,
+            extra_attack_dice: 0,
+            extra_mana_dice: 0,
+            timed_buffs: Vec::new(),
         }
     }
 }
@@ -83,35 +296,17 @@ impl Default for CombatManager {
 pub struct FightEvent {
     pub target: Entity,
     pub damage_amount: isize,
+    pub hits: usize,
     pub next_state: CombatState,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Inspectable, Component)]
-pub enum EnemyType {
-    Lizard,
-    Medusa,
-    Gin,
-    SmallDragon,
-    BigDragon,
-    Demon
-}
-
-impl EnemyType {
-    pub fn get_type(char_type: char) -> EnemyType {
-        match char_type {
-            'm' => Lizard,
-            'M' => Medusa,
-            'd' => SmallDragon,
-            'j' => Gin,
-            'D' => BigDragon,
-            's' => Demon,
-            _ => Demon
-        }
-    }
-}
+/// Key of an enemy template, the name map files refer to it by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Inspectable, Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyType(pub String);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
-pub struct EncounterEvent(pub EnemyType);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EncounterEvent(pub Vec<EnemyType>);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum CombatState {
@@ -174,3 +369,146 @@ pub struct TopItems;
 
 #[derive(Component)]
 pub struct Battleground;
+
+#[derive(Component)]
+pub struct StatusIcon;
+
+#[derive(Component)]
+pub struct BuffIcon;
+
+#[derive(Component)]
+pub struct HandCard;
+
+#[derive(Component)]
+pub struct DrawPileText;
+
+#[derive(Component)]
+pub struct DiscardPileText;
+
+/// Draw pile, hand and discard pile of the current fight, built from `Player.combat_deck`.
+#[derive(Default)]
+pub struct CombatDeck {
+    pub draw: Vec<CardView>,
+    pub hand: Vec<CardView>,
+    pub discard: Vec<CardView>,
+}
+
+impl CombatDeck {
+    pub fn new<R: Rng>(cards: &[CardView], rng: &mut R) -> Self {
+        let mut draw = cards.to_vec();
+        draw.shuffle(rng);
+
+        Self {
+            draw,
+            ..default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draw.is_empty() && self.hand.is_empty() && self.discard.is_empty()
+    }
+
+    /// Fills the hand up to `size`, shuffling the discard pile back in when the draw pile runs out.
+    pub fn draw_hand<R: Rng>(&mut self, size: usize, rng: &mut R) {
+        while self.hand.len() < size {
+            if self.draw.is_empty() {
+                if self.discard.is_empty() {
+                    break;
+                }
+                self.draw.append(&mut self.discard);
+                self.draw.shuffle(rng);
+            }
+
+            if let Some(card) = self.draw.pop() {
+                self.hand.push(card);
+            }
+        }
+    }
+
+    pub fn discard_hand(&mut self) {
+        self.discard.append(&mut self.hand);
+    }
+
+    pub fn play(&mut self, id: usize) {
+        if let Some(index) = self.hand.iter().position(|c| c.id == id) {
+            let card = self.hand.remove(index);
+            self.discard.push(card);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(count: usize) -> Vec<CardView> {
+        (1..=count).map(|id| CardView { id, level: 1 }).collect()
+    }
+
+    fn ids(cards: &[CardView]) -> Vec<usize> {
+        let mut ids = cards.iter().map(|c| c.id).collect::<Vec<usize>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn all_cards(deck: &CombatDeck) -> Vec<usize> {
+        ids(&[deck.draw.clone(), deck.hand.clone(), deck.discard.clone()].concat())
+    }
+
+    #[test]
+    fn draws_a_hand_of_n() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut deck = CombatDeck::new(&cards(6), &mut rng);
+
+        deck.draw_hand(3, &mut rng);
+        assert_eq!(deck.hand.len(), 3);
+        assert_eq!(deck.draw.len(), 3);
+        assert!(deck.discard.is_empty());
+
+        // a deck smaller than the hand deals what it has
+        let mut small = CombatDeck::new(&cards(2), &mut rng);
+        small.draw_hand(3, &mut rng);
+        assert_eq!(small.hand.len(), 2);
+    }
+
+    #[test]
+    fn reshuffles_discard_when_draw_runs_out() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut deck = CombatDeck::new(&cards(5), &mut rng);
+
+        deck.draw_hand(3, &mut rng);
+        let played = deck.hand[0].id;
+        deck.play(played);
+        assert_eq!(deck.discard.len(), 1);
+        deck.discard_hand();
+        assert_eq!(deck.draw.len(), 2);
+        assert_eq!(deck.discard.len(), 3);
+
+        deck.draw_hand(3, &mut rng);
+        assert_eq!(deck.hand.len(), 3);
+        assert_eq!(deck.draw.len() + deck.discard.len(), 2);
+        assert_eq!(all_cards(&deck), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn played_cards_stay_in_player_deck() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let player = Player {
+            combat_deck: cards(4),
+            ..default()
+        };
+        let mut deck = CombatDeck::new(&player.combat_deck, &mut rng);
+
+        for _ in 0..5 {
+            deck.discard_hand();
+            deck.draw_hand(3, &mut rng);
+            for id in ids(&deck.hand) {
+                deck.play(id);
+            }
+            assert!(deck.hand.is_empty());
+            assert_eq!(all_cards(&deck), vec![1, 2, 3, 4]);
+        }
+
+        assert_eq!(ids(&player.combat_deck), vec![1, 2, 3, 4]);
+    }
+}
@@ -1,4 +1,7 @@
+mod ai;
 mod components;
+mod rules;
+mod status;
 mod systems;
 
 use crate::prelude::*;
@@ -6,6 +9,9 @@ pub use crate::combat::components::*;
 pub use crate::combat::components::CombatState::*;
 pub use crate::GameState;
 pub use crate::combat::systems::*;
+pub use crate::combat::ai::*;
+pub use crate::combat::rules::*;
+pub use crate::combat::status::*;
 
 pub struct CombatPlugin;
 
@@ -15,15 +21,21 @@ impl Plugin for CombatPlugin {
             .add_state(PreState)
             .add_event::<FightEvent>()
             .init_resource::<CombatManager>()
+            .init_resource::<CombatDeck>()
             .add_system_set(
-                SystemSet::on_update(EnemyTurn)
+                SystemSet::on_enter(EnemyTurn)
                     .with_system(enemy_turn)
             )
+            .add_system_set(
+                SystemSet::on_enter(PlayerTurn)
+                    .with_system(deal_hand)
+            )
             .add_system_set(
                 SystemSet::on_update(PlayerTurn)
                     .with_system(attack_dice_roll)
                     .with_system(mana_dice_roll)
-                    .with_system(attack_button)
+                    .with_system(select_target)
+                    .with_system(attack_button.after(select_target))
                     .with_system(skip_button)
                     .with_system(use_card)
                     .with_system(update_damage_text.after(use_card))
@@ -31,11 +43,22 @@ impl Plugin for CombatPlugin {
                     .with_system(update_defense_text.after(use_card))
                     .with_system(update_health_text.after(use_card))
             )
+            .add_system_set(
+                SystemSet::on_enter(PlayerAttack)
+                    .with_system(play_combat_animation)
+            )
+            .add_system_set(
+                SystemSet::on_enter(EnemyAttack)
+                    .with_system(play_combat_animation)
+            )
+            .add_system_set(
+                SystemSet::on_enter(EnemyDeath)
+                    .with_system(play_combat_animation)
+            )
             .add_system_set(
                 SystemSet::on_enter(Finalize)
-                    .with_system(update_enemy_health_text.before(finalize))
-                    .with_system(update_health_text.before(finalize))
                     .with_system(finalize)
+                    .with_system(update_health_text.after(finalize))
                     .with_system(update_attack_dice_sprite.after(finalize))
                     .with_system(update_mana_dice_sprite.after(finalize))
                     .with_system(update_round_text.after(finalize))
@@ -45,6 +68,10 @@ impl Plugin for CombatPlugin {
                     .with_system(combat_camera)
                     .with_system(damage_calculation)
                     .with_system(enemy_attack_effect)
+                    .with_system(update_enemy_health_text.after(damage_calculation))
+                    .with_system(update_status_icons)
+                    .with_system(update_buff_icons)
+                    .with_system(update_pile_text)
             )
             .add_system_set(
                 SystemSet::on_enter(Combat)
@@ -68,6 +95,8 @@ impl Plugin for CombatPlugin {
                     .with_system(despawn_bottom_items)
                     .with_system(despawn_top_items)
                     .with_system(despawn_battleground)
+                    .with_system(despawn_status_icons)
+                    .with_system(despawn_hand)
                     .with_system(manager_default)
             );
     }
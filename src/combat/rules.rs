@@ -0,0 +1,27 @@
+use crate::combat::*;
+use crate::prelude::*;
+
+pub fn roll_die<R: Rng>(rng: &mut R) -> isize {
+    rng.gen_range(1..7)
+}
+
+pub fn pure_damage(damage_amount: isize, defense: isize, hits: usize) -> isize {
+    std::cmp::max(damage_amount - defense, 0) * hits as isize
+}
+
+pub fn apply_damage(target: &mut CombatStats, damage: isize) -> bool {
+    target.health = std::cmp::max(target.health - damage, 0);
+    target.health == 0
+}
+
+pub fn enemy_combat_stats(template: &Template, buff: isize) -> CombatStats {
+    let health = template.health.unwrap() as isize + buff;
+
+    CombatStats {
+        health,
+        attack: template.attack.unwrap() as isize + buff,
+        defense: template.defense.unwrap() as isize + buff,
+        max_health: health,
+        mana: 0,
+    }
+}
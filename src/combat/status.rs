@@ -0,0 +1,149 @@
+use serde::Deserialize;
+use crate::combat::*;
+use crate::prelude::*;
+use crate::combat::StatusKind::*;
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    Poison,
+    Burn,
+    Stun,
+    Regen,
+    Shield,
+}
+
+impl StatusKind {
+    /// Harmful statuses land on the opponent, the others on the caster.
+    pub fn is_harmful(&self) -> bool {
+        matches!(self, Poison | Burn | Stun)
+    }
+
+    /// Combat icon index and tint.
+    pub fn icon(&self) -> (usize, Color) {
+        match self {
+            Poison => (3, POISON_COLOR),
+            Burn => (2, BURN_COLOR),
+            Stun => (1, STUN_COLOR),
+            Regen => (4, WHITE),
+            Shield => (5, SHIELD_COLOR),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    #[serde(default = "StatusEffect::default_stacks")]
+    pub stacks: isize,
+    /// Rounds left, 0 lasts until the end of combat.
+    #[serde(default = "StatusEffect::default_rounds")]
+    pub rounds: usize,
+    /// Applied this round, the countdown starts with the next one.
+    #[serde(skip)]
+    pub fresh: bool,
+}
+
+impl StatusEffect {
+    /// Stacks and rounds left, as shown under the status icon.
+    pub fn counter(&self) -> String {
+        match (self.kind, self.rounds) {
+            (_, 0) => self.stacks.to_string(),
+            (Stun, rounds) => rounds.to_string(),
+            (_, rounds) => format!("{}/{}", self.stacks, rounds),
+        }
+    }
+
+    fn default_stacks() -> isize {
+        1
+    }
+
+    fn default_rounds() -> usize {
+        1
+    }
+}
+
+#[derive(Component, Clone, Debug, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+    pub stunned: bool,
+}
+
+impl StatusEffects {
+    pub fn add(&mut self, kind: StatusKind, stacks: isize, rounds: usize) {
+        if stacks <= 0 {
+            return;
+        }
+
+        match self.effects.iter_mut().find(|e| e.kind == kind) {
+            Some(effect) => {
+                effect.stacks += stacks;
+                effect.rounds = if effect.rounds == 0 || rounds == 0 {
+                    0
+                } else {
+                    std::cmp::max(effect.rounds, rounds)
+                };
+                effect.fresh = true;
+            }
+            None => self.effects.push(StatusEffect { kind, stacks, rounds, fresh: true }),
+        }
+    }
+
+    pub fn apply(&mut self, effect: &StatusEffect) {
+        self.add(effect.kind, effect.stacks, effect.rounds);
+    }
+
+    pub fn stacks(&self, kind: StatusKind) -> isize {
+        self.effects
+            .iter()
+            .find(|e| e.kind == kind)
+            .map_or(0, |e| e.stacks)
+    }
+
+    /// Regen heals, poison and burn hurt and stun is picked up for the new round.
+    /// Returns true if the holder died.
+    pub fn round_start(&mut self, stats: &mut CombatStats) -> bool {
+        let heal = self.stacks(Regen);
+        let hurt = self.stacks(Poison) + self.stacks(Burn);
+        self.stunned = self.stacks(Stun) > 0;
+
+        // poison wears off a stack every round
+        if let Some(poison) = self.effects.iter_mut().find(|e| e.kind == Poison) {
+            poison.stacks -= 1;
+        }
+        self.effects.retain(|e| e.stacks > 0);
+
+        stats.health = std::cmp::min(stats.health + heal, stats.max_health);
+        apply_damage(stats, hurt)
+    }
+
+    pub fn round_end(&mut self) {
+        for effect in self.effects.iter_mut() {
+            if effect.fresh {
+                effect.fresh = false;
+            } else if effect.rounds > 0 {
+                effect.rounds -= 1;
+                if effect.rounds == 0 {
+                    effect.stacks = 0;
+                }
+            }
+        }
+
+        self.effects.retain(|e| e.stacks > 0);
+        self.stunned = false;
+    }
+
+    /// Shield soaks incoming damage, losing a stack per point absorbed.
+    pub fn absorb(&mut self, damage: isize) -> isize {
+        let left = match self.effects.iter_mut().find(|e| e.kind == Shield) {
+            Some(shield) => {
+                let absorbed = std::cmp::min(shield.stacks, damage);
+                shield.stacks -= absorbed;
+                damage - absorbed
+            }
+            None => damage,
+        };
+
+        self.effects.retain(|e| e.stacks > 0);
+        left
+    }
+}
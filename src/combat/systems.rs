@@ -1,4 +1,3 @@
-use crate::combat::EnemyType::*;
 use crate::CombatState::*;
 use crate::combat::*;
 use crate::player::*;
@@ -9,63 +8,28 @@ pub fn use_card(
     mut selected_query: Query<(&mut Card, &Selected, &Children)>,
     mut manager: ResMut<CombatManager>,
     mut player_stats_query: Query<&mut CombatStats, With<Player>>,
-    mut player_query: Query<&mut Player>,
+    mut player_query: Query<&mut StatusEffects, With<Player>>,
+    mut target_query: Query<&mut StatusEffects, TargetFilter>,
     mut visibility: Query<&mut Visibility, Without<Card>>,
+    mut deck: ResMut<CombatDeck>,
 ) {
     let mut player_stats = player_stats_query.single_mut();
-    let mut player = player_query.single_mut();
+    let mut statuses = player_query.single_mut();
+    let mut target_statuses = target_query.get_single_mut().ok();
     for (mut card, selected, children) in selected_query.iter_mut() {
-        if selected.selected && manager.if_can_cast(card.mana_cost, card.is_used) {
-            match card.card_action {
-                CardAction::AttackBuff => {
-                    if CombatManager::is_range_buff(&card) {
-                        manager.permanent_damage_buff += card.value as isize;
-                    } else {
-                        manager.damage += card.value as isize;
-                    }
-
-                    manager.print();
-                }
-                CardAction::DefenceBuff => {
-                    manager.permanent_defense_buff += card.value as isize;
-                    manager.print();
-                }
-                CardAction::HealthBuff => {
-                    if player_stats.health + card.value as isize > player_stats.max_health {
-                        player_stats.health = player_stats.max_health;
-                    } else {
-                        player_stats.health += card.value as isize;
-                    }
-                    manager.print();
-                }
-                CardAction::ManaBuff => {
-                    manager.permanent_mana_buff += card.value as isize;
-                    manager.print();
-                }
-                CardAction::Special => {
-                    match card.name.as_ref() {
-                        "Fear" => {
-                            manager.enemy_skip_round = true;
-                        }
-                        "Spirit Seance" => {
-                            manager.permanent_damage_buff = manager.round as isize;
-                            manager.damage += manager.round as isize;
-                        }
-                        "Takeover" => {
-                            player_stats.max_health += 1;
-                            player_stats.health += 1;
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            let index = player.combat_deck
-                .iter()
-                .position(|c| c.id == card.id)
-                .unwrap();
-            player.combat_deck.remove(index);
+        if selected.selected
+            && manager.cast(
+                card.mana_cost,
+                card.is_used,
+                &card.effects,
+                &mut player_stats,
+                &mut statuses,
+                target_statuses.as_deref_mut(),
+            )
+        {
+            manager.print();
 
+            deck.play(card.id);
 
             for child in children.iter() {
                 if let Ok(mut vis) = visibility.get_mut(*child) {
@@ -115,11 +79,18 @@ pub fn update_round_text(
 }
 
 pub fn update_enemy_health_text(
-    text_query: Query<&mut Text, (With<HealthText>, Without<PlayerMarker>)>,
-    enemy_query: Query<&CombatStats, With<Enemy>>,
+    mut text_query: Query<&mut Text, (With<HealthText>, With<EnemyMarker>)>,
+    enemy_query: Query<(&CombatStats, &Children), With<Enemy>>,
 ) {
-    let enemy_hp = enemy_query.single().health;
-    update_text(text_query, enemy_hp);
+    for (stats, children) in enemy_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                for section in text.sections.iter_mut() {
+                    section.value = stats.health.to_string();
+                }
+            }
+        }
+    }
 }
 
 pub fn update_attack_dice_sprite(dice_query: Query<&mut TextureAtlasSprite, With<AttackDice>>) {
@@ -153,14 +124,15 @@ pub fn update_text<T: Component, M: Component>(
 pub fn attack_dice_roll(
     mut selected_query: Query<(&Selected, &mut TextureAtlasSprite), With<AttackDice>>,
     mut manager: ResMut<CombatManager>,
+    mut rng: ResMut<GameRng>,
     text_query: Query<&mut Text, (With<AttackText>, Without<EnemyMarker>)>,
 ) {
     let (selected, mut atlas) = selected_query.single_mut();
 
     if selected.selected && manager.can_roll_attack {
-        let roll = thread_rng().gen_range(1..7);
-        manager.damage += roll;
-        manager.can_roll_attack = false;
+        let roll = roll_die(&mut rng.combat);
+        let extra: isize = (0..manager.extra_attack_dice).map(|_| roll_die(&mut rng.combat)).sum();
+        manager.roll_attack(roll + extra);
         atlas.index = roll as usize;
 
         update_text(text_query, manager.damage);
@@ -171,14 +143,15 @@ pub fn attack_dice_roll(
 pub fn mana_dice_roll(
     mut selected_query: Query<(&Selected, &mut TextureAtlasSprite), With<ManaDice>>,
     mut manager: ResMut<CombatManager>,
+    mut rng: ResMut<GameRng>,
     text_query: Query<&mut Text, (With<ManaText>, Without<EnemyMarker>)>,
 ) {
     let (selected, mut atlas) = selected_query.single_mut();
 
     if selected.selected && manager.can_roll_mana {
-        let roll = thread_rng().gen_range(1..7);
-        manager.mana_poll += roll;
-        manager.can_roll_mana = false;
+        let roll = roll_die(&mut rng.combat);
+        let extra: isize = (0..manager.extra_mana_dice).map(|_| roll_die(&mut rng.combat)).sum();
+        manager.roll_mana(roll + extra);
         atlas.index = 7 + roll as usize;
 
         update_text(text_query, manager.mana_poll);
@@ -190,26 +163,77 @@ pub fn attack_button(
     selected_query: Query<&Selected, With<AttackButton>>,
     manager: ResMut<CombatManager>,
     mut fight_event: EventWriter<FightEvent>,
-    enemy_query: Query<Entity, With<Enemy>>,
+    target_query: Query<Entity, TargetFilter>,
 ) {
     let selected = selected_query.single().selected;
-    let enemy = enemy_query.single();
+    let enemy = match target_query.get_single() {
+        Ok(target) => target,
+        Err(_) => return,
+    };
 
     if selected && !manager.can_roll_attack && !manager.skip_round {
         println!("attack");
         fight_event.send(FightEvent {
             target: enemy,
             damage_amount: manager.damage,
+            hits: 1,
             next_state: PlayerAttack,
         });
     }
 }
 
+pub fn select_target(
+    mut commands: Commands,
+    cursor_state: Res<CursorState>,
+    buttons: Res<Input<MouseButton>>,
+    mut enemy_query: Query<(Entity, &Enemy, &CombatStats, &Transform, &mut TextureAtlasSprite)>,
+    target_query: Query<Entity, TargetFilter>,
+) {
+    let current = target_query.get_single().ok();
+    let mut alive = enemy_query
+        .iter()
+        .filter(|(_, _, stats, ..)| stats.is_alive())
+        .map(|(entity, enemy, _, transform, sprite)| {
+            let size = sprite.custom_size.unwrap_or(Vec2::ONE);
+            (entity, enemy.slot, transform.translation.truncate(), size)
+        })
+        .collect::<Vec<_>>();
+    alive.sort_by_key(|(_, slot, ..)| *slot);
+
+    let click = cursor_state.last_left_click_float;
+    let clicked = if buttons.just_pressed(MouseButton::Left) {
+        alive
+            .iter()
+            .find(|(_, _, pos, size)| {
+                (click.x - pos.x).abs() <= size.x / 2. && (click.y - pos.y).abs() <= size.y / 2.
+            })
+            .map(|(entity, ..)| *entity)
+    } else {
+        None
+    };
+
+    let target = clicked
+        .or_else(|| current.filter(|entity| alive.iter().any(|(e, ..)| e == entity)))
+        .or_else(|| alive.first().map(|(entity, ..)| *entity));
+
+    for (entity, _, stats, _, mut sprite) in enemy_query.iter_mut() {
+        let is_target = Some(entity) == target;
+
+        if is_target && current != Some(entity) {
+            commands.entity(entity).insert(Target);
+        } else if !is_target && current == Some(entity) {
+            commands.entity(entity).remove::<Target>();
+        }
+
+        sprite.color = if is_target || !stats.is_alive() { WHITE } else { UNTARGETED_COLOR };
+    }
+}
+
 pub fn skip_button(
     selected_query: Query<&Selected, With<HeroSpellButton>>,
     mut manager: ResMut<CombatManager>,
     mut combat_state: ResMut<State<CombatState>>,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let selected = selected_query.single().selected;
 
@@ -230,7 +254,8 @@ pub fn skip_button(
 
 pub fn damage_calculation(
     mut fight_event: EventReader<FightEvent>,
-    mut target_query: Query<&mut CombatStats>,
+    mut target_query: Query<(&mut CombatStats, Option<&mut StatusEffects>)>,
+    enemy_query: Query<Entity, With<Enemy>>,
     mut combat_state: ResMut<State<CombatState>>,
     mut manager: ResMut<CombatManager>,
 ) {
@@ -244,28 +269,32 @@ pub fn damage_calculation(
     }
 
     if let Some(fight_event) = fight_event.iter().next() {
-        let mut target_stats = target_query
+        let (mut target_stats, target_statuses) = target_query
             .get_mut(fight_event.target)
             .expect("Fight target without stats!");
 
-        let pure_damage = if combat_state.current() == &PlayerTurn {
-            std::cmp::max(
-                fight_event.damage_amount - target_stats.defense,
-                0,
-            )
+        let defense = if combat_state.current() == &PlayerTurn {
+            target_stats.defense
         } else {
-            std::cmp::max(
-                fight_event.damage_amount - manager.defense,
-                0,
-            )
+            manager.defense
         };
+        let mut damage = pure_damage(fight_event.damage_amount, defense, fight_event.hits);
+        if let Some(mut statuses) = target_statuses {
+            damage = statuses.absorb(damage);
+        }
 
-        target_stats.health = std::cmp::max(target_stats.health - pure_damage, 0);
-        if target_stats.health == 0 {
+        if apply_damage(&mut target_stats, damage) {
             if fight_event.next_state == PlayerAttack {
-                manager.enemy_death = true;
-                combat_state.set(EnemyDeath).unwrap();
-                return;
+                let all_dead = enemy_query
+                    .iter()
+                    .filter_map(|enemy| target_query.get(enemy).ok())
+                    .all(|(stats, _)| !stats.is_alive());
+
+                if all_dead {
+                    manager.enemy_death = true;
+                    combat_state.set(EnemyDeath).unwrap();
+                    return;
+                }
             } else {
                 manager.player_death = true;
             }
@@ -299,22 +328,39 @@ pub fn combat_end_button(
     }
 
     if selected && manager.player_death {
-        pl_transform.translation = Vec3::new(6., -3., 500.);
+        pl_transform.translation = Vec3::new(START_TILE.0 as f32, START_TILE.1 as f32, 500.);
         state.set(World).expect("fail state");
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn end_combat(
     mut commands: Commands,
     mut items: ResMut<ItemPull>,
+    mut rng: ResMut<GameRng>,
     manager: ResMut<CombatManager>,
-    mut player_query: Query<&mut Player>,
+    mut player_query: Query<(&mut Player, &mut CombatStats)>,
     texture_storage: Res<TextureStorage>,
     template_storage: Res<TemplateStorage>,
+    progression: Res<Progression>,
+    classes: Res<HeroClasses>,
 ) {
-    let mut player = player_query.single_mut();
+    let (mut player, mut stats) = player_query.single_mut();
+    let class = classes.get(&player.class);
     let text = if manager.enemy_death { "Get reward" } else { "Respawn" };
 
+    if manager.enemy_death {
+        player.xp += progression.enemy_reward(manager.enemy_lvl);
+
+        if let ClassPassive::VictoryHeal(heal) = class.passive {
+            stats.health = std::cmp::min(stats.health + heal, stats.max_health);
+        }
+    }
+
+    if manager.max_health_gain != 0 {
+        player.add_reward(StatModifier { stat: Stat::MaxHealth, value: manager.max_health_gain });
+    }
+
     let text_ent = spawn_text(
         &mut commands,
         &texture_storage,
@@ -331,7 +377,7 @@ pub fn end_combat(
 
     let button = if manager.enemy_death {
         if let Some(reward) = get_reward_template(
-            &template_storage, manager.enemy_lvl, &mut items,
+            &template_storage, manager.enemy_lvl, &mut items, &mut rng, &class.cards,
         ) {
             add_reward(&reward, &mut player);
             spawn_reward_button(
@@ -375,123 +421,70 @@ pub fn end_combat(
         .id();
 }
 
-pub fn enemy_turn(
-    mut fight_event: EventWriter<FightEvent>,
-    enemy_query: Query<&CombatStats, With<Enemy>>,
-    player_query: Query<Entity, With<Player>>,
+/// Starts the clip the new combat state shows on the target or the acting enemy.
+pub fn play_combat_animation(
+    combat_state: Res<State<CombatState>>,
+    manager: Res<CombatManager>,
+    mut enemy_query: Query<(&Enemy, &CombatStats, &mut Animator, Option<&Target>)>,
 ) {
-    let player_ent = player_query.single();
-    let enemy_stats = enemy_query.iter().next().unwrap();
-
-    fight_event.send(FightEvent {
-        target: player_ent,
-        damage_amount: enemy_stats.attack,
-        next_state: EnemyAttack,
-    });
+    for (enemy, stats, mut animator, target) in enemy_query.iter_mut() {
+        match combat_state.current() {
+            PlayerAttack | EnemyDeath if target.is_some() => {
+                animator.play(if stats.is_alive() { "hurt" } else { "death" });
+            }
+            EnemyAttack if enemy.slot == manager.acting_enemy => animator.play("attack"),
+            _ => {}
+        }
+    }
 }
 
+/// Moves combat on once the clip of the target or the acting enemy is over.
 pub fn enemy_attack_effect(
-    mut enemy_frame_query: Query<(&mut FrameAnimation, &mut EncounterTracker), With<Enemy>>,
+    mut finished_event: EventReader<AnimationFinished>,
+    enemy_query: Query<(&Enemy, &CombatStats, Option<&Target>)>,
     mut combat_state: ResMut<State<CombatState>>,
-    frame_sheet: ResMut<FramesSheet>,
-    time: Res<Time>,
-    manager: ResMut<CombatManager>,
-    enemy_query: Query<&Enemy>,
+    mut manager: ResMut<CombatManager>,
 ) {
-    let enemy_type = enemy_query.single().enemy_type;
-
-    let idle_frames = match enemy_type {
-        Lizard => frame_sheet.lizard_idle.to_vec(),
-        Medusa => frame_sheet.medusa_idle.to_vec(),
-        SmallDragon => frame_sheet.small_dragon_idle.to_vec(),
-        Gin => frame_sheet.gin_idle.to_vec(),
-        BigDragon => frame_sheet.big_dragon_idle.to_vec(),
-        Demon => frame_sheet.demon_idle.to_vec(),
-    };
-
-    let attack_frames = match enemy_type {
-        Lizard => frame_sheet.lizard_attack.to_vec(),
-        Medusa => frame_sheet.medusa_attack.to_vec(),
-        SmallDragon => frame_sheet.small_dragon_attack.to_vec(),
-        Gin => frame_sheet.gin_attack.to_vec(),
-        BigDragon => frame_sheet.big_dragon_attack.to_vec(),
-        Demon => frame_sheet.demon_attack.to_vec(),
-    };
-
-    let hurt_frames = match enemy_type {
-        Lizard => frame_sheet.lizard_hurt.to_vec(),
-        Medusa => frame_sheet.medusa_hurt.to_vec(),
-        SmallDragon => frame_sheet.small_dragon_hurt.to_vec(),
-        Gin => frame_sheet.gin_hurt.to_vec(),
-        BigDragon => frame_sheet.big_dragon_hurt.to_vec(),
-        Demon => frame_sheet.demon_hurt.to_vec(),
-    };
-
-    let death_frames = match enemy_type {
-        Lizard => frame_sheet.lizard_death.to_vec(),
-        Medusa => frame_sheet.medusa_death.to_vec(),
-        SmallDragon => frame_sheet.small_dragon_death.to_vec(),
-        Gin => frame_sheet.gin_death.to_vec(),
-        BigDragon => frame_sheet.big_dragon_death.to_vec(),
-        Demon => frame_sheet.demon_death.to_vec(),
-    };
-
-    let last_death_frame = match enemy_type {
-        Lizard => death_frames[5],
-        Medusa => death_frames[5],
-        SmallDragon => death_frames[3],
-        Gin => death_frames[5],
-        BigDragon => death_frames[4],
-        Demon => death_frames[5],
-    };
-
-
-    for (mut effect, mut encounter_tracker) in enemy_frame_query.iter_mut() {
-        encounter_tracker.timer.tick(time.delta());
+    let state = *combat_state.current();
+    let finished = finished_event.iter().any(|event| match enemy_query.get(event.entity) {
+        Ok((enemy, _, target)) => match state {
+            PlayerAttack | EnemyDeath => target.is_some() && (event.state == "hurt" || event.state == "death"),
+            EnemyAttack => enemy.slot == manager.acting_enemy && event.state == "attack",
+            _ => false,
+        },
+        Err(_) => false,
+    });
 
-        match combat_state.current() {
-            PlayerTurn => effect.frames = idle_frames.clone(),
-            PlayerAttack => {
-                println!("PlayerAttack state");
-                effect.frames = hurt_frames.clone();
+    if !finished {
+        return;
+    }
 
-                if encounter_tracker.timer.just_finished() && manager.enemy_death {
-                    combat_state.set(End).unwrap();
-                }
+    let alive_slots = enemy_query
+        .iter()
+        .filter(|(_, stats, _)| stats.is_alive())
+        .map(|(enemy, ..)| enemy.slot)
+        .collect::<Vec<usize>>();
 
-                if encounter_tracker.timer.just_finished() && manager.enemy_skip_round {
-                    combat_state.set(Finalize).unwrap();
-                } else if encounter_tracker.timer.just_finished() && !manager.enemy_skip_round {
-                    combat_state.set(EnemyTurn).unwrap();
-                }
-            }
-            EnemyAttack => {
-                println!("EnemyAttack state");
-                effect.frames = attack_frames.clone();
-
-                if encounter_tracker.timer.just_finished() {
-                    if manager.player_death {
-                        combat_state.set(End).unwrap();
-                    } else {
-                        combat_state.set(Finalize).unwrap();
-                    }
-                }
-            }
-            EnemyDeath => {
-                effect.frames = death_frames.clone();
-                if encounter_tracker.timer.just_finished() {
-                    combat_state.set(End).unwrap();
-                }
+    match state {
+        PlayerAttack => {
+            if manager.enemy_skip_round || alive_slots.is_empty() {
+                combat_state.set(Finalize).unwrap();
+            } else {
+                combat_state.set(EnemyTurn).unwrap();
             }
-            End => {
-                if manager.enemy_death {
-                    effect.frames = vec![last_death_frame];
-                } else {
-                    effect.frames = idle_frames.clone();
-                }
+        }
+        EnemyAttack => {
+            if manager.player_death {
+                combat_state.set(End).unwrap();
+            } else if let Some(next) = alive_slots.iter().filter(|slot| **slot > manager.acting_enemy).min() {
+                manager.acting_enemy = *next;
+                combat_state.set(EnemyTurn).unwrap();
+            } else {
+                combat_state.set(Finalize).unwrap();
             }
-            _ => {}
         }
+        EnemyDeath => combat_state.set(End).unwrap(),
+        _ => {}
     }
 }
 
@@ -505,6 +498,72 @@ pub fn combat_camera(mut camera_query: Query<&mut Transform, With<Camera>>) {
     camera_transform.translation.y = 0.0;
 }
 
+pub fn card_slot(index: usize) -> Transform {
+    Transform::from_xyz(-8.5 + index as f32 * 2.5, -4., 100.)
+}
+
+pub fn deal_hand(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    template_storage: Res<TemplateStorage>,
+    mut deck: ResMut<CombatDeck>,
+    mut rng: ResMut<GameRng>,
+    player_query: Query<&Player>,
+    hand_query: Query<Entity, With<HandCard>>,
+) {
+    for entity in hand_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if deck.is_empty() {
+        *deck = CombatDeck::new(&player_query.single().combat_deck, &mut rng.combat);
+    }
+
+    deck.discard_hand();
+    deck.draw_hand(HAND_SIZE, &mut rng.combat);
+
+    for index in 0..HAND_SIZE {
+        let id = deck.hand.get(index).map_or(0, |c| c.id);
+        let card = spawn_combat_card(
+            &mut commands,
+            &texture_storage,
+            &card_slot(index),
+            &template_storage,
+            id,
+        );
+        commands.entity(card).insert(HandCard);
+    }
+}
+
+pub fn update_pile_text(
+    deck: Res<CombatDeck>,
+    mut draw_query: Query<&mut Text, (With<DrawPileText>, Without<DiscardPileText>)>,
+    mut discard_query: Query<&mut Text, (With<DiscardPileText>, Without<DrawPileText>)>,
+) {
+    if !deck.is_changed() {
+        return;
+    }
+
+    for mut text in draw_query.iter_mut() {
+        text.sections[0].value = format!("Draw {}", deck.draw.len());
+    }
+    for mut text in discard_query.iter_mut() {
+        text.sections[0].value = format!("Discard {}", deck.discard.len());
+    }
+}
+
+pub fn despawn_hand(
+    mut commands: Commands,
+    hand_query: Query<Entity, With<HandCard>>,
+    mut deck: ResMut<CombatDeck>,
+) {
+    for entity in hand_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    *deck = CombatDeck::default();
+}
+
 pub fn despawn_bottom_items(
     mut commands: Commands,
     enemy_query: Query<Entity, With<BottomItems>>,
@@ -547,125 +606,115 @@ pub fn despawn_battleground(
 
 pub fn spawn_enemy(
     mut commands: Commands,
-    frame_sheet: Res<FramesSheet>,
     texture_storage: Res<TextureStorage>,
     template_storage: Res<TemplateStorage>,
+    animations: Res<AnimationLibrary>,
     mut encounter_event: EventReader<EncounterEvent>,
     mut manager: ResMut<CombatManager>,
     next_button_query: Query<&NextButton>
 ) {
     if let Some(event) = encounter_event.iter().next() {
         let enemy_stat_buff = next_button_query.single().enemy_buff();
-        let enemy_type = event.0;
-        let enemy_stats = template_storage.get_enemy(enemy_type).unwrap();
-
-        match enemy_type {
-            Lizard | Medusa => manager.enemy_lvl = 1,
-            Gin | SmallDragon => manager.enemy_lvl = 2,
-            BigDragon | Demon => manager.enemy_lvl = 3
+        let enemy_types = event.0
+            .iter()
+            .take(MAX_ENCOUNTER_ENEMIES)
+            .cloned()
+            .collect::<Vec<EnemyType>>();
+
+        if let Some(enemy_type) = enemy_types.first() {
+            spawn_combat_battleground(
+                &mut commands,
+                &template_storage.enemy_sprite(enemy_type).battleground,
+                Transform::from_xyz(0., 0., 50.)
+            );
         }
 
-        let enemy_combat_stats = CombatStats {
-            health: enemy_stats.health.unwrap() as isize + enemy_stat_buff as isize,
-            attack: enemy_stats.attack.unwrap() as isize + enemy_stat_buff as isize,
-            defense: enemy_stats.defense.unwrap() as isize + enemy_stat_buff as isize,
-            max_health: enemy_stats.health.unwrap() as isize + enemy_stat_buff as isize,
-            mana: 0,
-        };
-
-        let attack_text_translation = match enemy_type {
-            Lizard => Vec3::new(-0.2, -1.4, 205.0),
-            Medusa => Vec3::new(-0.2, -1.7, 205.0),
-            SmallDragon => Vec3::new(-0.2, -1.4, 205.0),
-            Gin => Vec3::new(-0.2, -1.7, 205.0),
-            BigDragon => Vec3::new(0., -2.2, 205.0),
-            Demon => Vec3::new(-0.2, -1.9, 205.0),
-        };
-
-        let defense_text_translation = match enemy_type {
-            Lizard => Vec3::new(-0.2, -2., 205.0),
-            Medusa => Vec3::new(-0.2, -2.3, 205.0),
-            SmallDragon => Vec3::new(-0.2, -2., 205.0),
-            Gin => Vec3::new(-0.2, -2.3, 205.0),
-            BigDragon => Vec3::new(0., -2.8, 205.0),
-            Demon => Vec3::new(-0.2, -2.5, 205.0),
-        };
+        for (slot, enemy_type) in enemy_types.iter().enumerate() {
+            let offset = Enemy::slot_offset(slot, enemy_types.len());
+            let enemy_stats = template_storage.get_enemy(enemy_type).unwrap();
+            let enemy_sprite = template_storage.enemy_sprite(enemy_type);
 
-        let health_text_translation = match enemy_type {
-            Lizard => Vec3::new(-0.2, -2.6, 205.0),
-            Medusa => Vec3::new(-0.2, -2.9, 205.0),
-            SmallDragon => Vec3::new(-0.2, -2.6, 205.0),
-            Gin => Vec3::new(-0.2, -2.9, 205.0),
-            BigDragon => Vec3::new(0., -3.4, 205.0),
-            Demon => Vec3::new(-0.2, -3.1, 205.0),
-        };
+            manager.enemy_lvl = std::cmp::max(manager.enemy_lvl, enemy_stats.level);
 
-        let attack_text = spawn_text(
-            &mut commands,
-            &texture_storage,
-            Transform {
-                translation: attack_text_translation,
-                scale: Vec3::new(0.01, 0.01, 0.),
-                ..default()
-            },
-            enemy_combat_stats.attack.to_string(),
-            "Enemy attack text".to_string(),
-            AttackText,
-            EnemyMarker,
-        );
+            let enemy_combat_stats = enemy_combat_stats(enemy_stats, enemy_stat_buff as isize);
 
-        let defense_text = spawn_text(
-            &mut commands,
-            &texture_storage,
-            Transform {
-                translation: defense_text_translation,
-                scale: Vec3::new(0.01, 0.01, 0.),
-                ..default()
-            },
-            enemy_combat_stats.defense.to_string(),
-            "Enemy defense text".to_string(),
-            DefenseText,
-            EnemyMarker,
-        );
+            let (text_x, text_y) = enemy_sprite.stats_position;
+            let attack_text_translation = Vec3::new(text_x, text_y, 205.0);
+            let defense_text_translation = Vec3::new(text_x, text_y - 0.6, 205.0);
+            let health_text_translation = Vec3::new(text_x, text_y - 1.2, 205.0);
 
-        let health_text = spawn_text(
-            &mut commands,
-            &texture_storage,
-            Transform {
-                translation: health_text_translation,
-                scale: Vec3::new(0.01, 0.01, 0.),
-                ..default()
-            },
-            enemy_combat_stats.health.to_string(),
-            "Enemy health text".to_string(),
-            HealthText,
-            EnemyMarker,
-        );
+            let attack_text = spawn_text(
+                &mut commands,
+                &texture_storage,
+                Transform {
+                    translation: attack_text_translation,
+                    scale: Vec3::new(0.01, 0.01, 0.),
+                    ..default()
+                },
+                enemy_combat_stats.attack.to_string(),
+                "Enemy attack text".to_string(),
+                AttackText,
+                EnemyMarker,
+            );
+
+            let defense_text = spawn_text(
+                &mut commands,
+                &texture_storage,
+                Transform {
+                    translation: defense_text_translation,
+                    scale: Vec3::new(0.01, 0.01, 0.),
+                    ..default()
+                },
+                enemy_combat_stats.defense.to_string(),
+                "Enemy defense text".to_string(),
+                DefenseText,
+                EnemyMarker,
+            );
+
+            let health_text = spawn_text(
+                &mut commands,
+                &texture_storage,
+                Transform {
+                    translation: health_text_translation,
+                    scale: Vec3::new(0.01, 0.01, 0.),
+                    ..default()
+                },
+                enemy_combat_stats.health.to_string(),
+                "Enemy health text".to_string(),
+                HealthText,
+                EnemyMarker,
+            );
+
+            let sprite = spawn_enemy_sprite(
+                &mut commands,
+                enemy_sprite,
+                &animations,
+                offset,
+            );
 
-        let sprite = spawn_enemy_sprite(
-            &mut commands,
-            &frame_sheet,
-            enemy_type,
-        );
+            spawn_top_bar(&mut commands, &texture_storage, offset);
 
-        spawn_combat_battleground(
-            &mut commands,
-            &texture_storage,
-            &enemy_type,
-            Transform::from_xyz(0., 0., 50.)
-        );
+            if slot == 0 {
+                commands.entity(sprite).insert(Target);
+            }
 
-        commands
-            .entity(sprite)
-            .insert(Enemy { enemy_type })
-            .insert(enemy_combat_stats)
-            .insert(Name::new("Enemy"))
-            .insert(EncounterTracker {
-                timer: Timer::from_seconds(3.5, true)
-            })
-            .push_children(&[attack_text])
-            .push_children(&[defense_text])
-            .push_children(&[health_text]);
+            let abilities = enemy_stats.enemy_abilities();
+            commands
+                .entity(sprite)
+                .insert(Enemy {
+                    enemy_type: enemy_type.clone(),
+                    slot,
+                    cooldowns: vec![0; abilities.len()],
+                    abilities,
+                    policy: enemy_stats.ability_policy.unwrap_or_default(),
+                })
+                .insert(enemy_combat_stats)
+                .insert(StatusEffects::default())
+                .insert(Name::new(format!("Enemy {}", slot)))
+                .push_children(&[attack_text])
+                .push_children(&[defense_text])
+                .push_children(&[health_text]);
+        }
     };
 }
 
@@ -676,8 +725,6 @@ pub fn spawn_interface(
     storage: Res<TemplateStorage>,
     player_stats_query: Query<&CombatStats, With<Player>>,
 ) {
-    let _top = spawn_top_bar(&mut commands, &texture_storage);
-
     let _bottom = spawn_bottom_bar(
         &mut commands,
         &texture_storage,
@@ -690,34 +737,35 @@ pub fn spawn_interface(
 pub fn spawn_top_bar(
     commands: &mut Commands,
     texture_storage: &TextureStorage,
+    offset: f32,
 ) -> Entity {
     let sprites = vec![
         spawn_background_element(
             commands,
             texture_storage,
             Some(Vec2::new(3., 5.)),
-            Transform::from_xyz(-1., 3., 100.),
+            Transform::from_xyz(-1. + offset, 3., 100.),
             "Enemy background",
         ),
-        spawn_enemy_border_frame(commands, texture_storage),
+        spawn_enemy_border_frame(commands, texture_storage, offset),
         spawn_combat_icon(
             commands,
             texture_storage,
-            Transform::from_xyz(-1.8, 2.6, 150.),
+            Transform::from_xyz(-1.8 + offset, 2.6, 150.),
             2,
             "Enemy attack icon",
         ),
         spawn_combat_icon(
             commands,
             texture_storage,
-            Transform::from_xyz(-1.8, 2., 150.),
+            Transform::from_xyz(-1.8 + offset, 2., 150.),
             5,
             "Enemy defense icon",
         ),
         spawn_combat_icon(
             commands,
             texture_storage,
-            Transform::from_xyz(-1.8, 1.4, 150.),
+            Transform::from_xyz(-1.8 + offset, 1.4, 150.),
             4,
             "Enemy health icon",
         ),
@@ -832,49 +880,45 @@ pub fn spawn_bottom_bar(
 ) -> Entity {
     let player = player_query.single();
     let mut sprites = Vec::new();
-    let cards_id = player.combat_deck.clone();
 
     let _text_stats = spawn_player_text(commands, player_stats_query, texture_storage);
 
-    let positions = vec![
-        Transform::from_xyz(-8.5, -4., 100.),
-        Transform::from_xyz(-6., -4., 100.),
-        Transform::from_xyz(-3.5, -4., 100.),
-        Transform::from_xyz(-1., -4., 100.),
-        Transform::from_xyz(1.5, -4., 100.),
-        Transform::from_xyz(4., -4., 100.),
+    let piles = [
+        (HAND_SIZE, format!("Draw {}", player.combat_deck.len())),
+        (HAND_SIZE + 1, "Discard 0".to_string()),
     ];
 
-    let mut pos = positions.iter();
-    let mut cards = cards_id.iter();
-
-    loop {
-        match (pos.next(), cards.next()) {
-            (Some(transform), Some(card)) => {
-                let card = spawn_combat_card(
-                    commands,
-                    texture_storage,
-                    transform,
-                    template_storage,
-                    card.id,
-                );
-
-                sprites.push(card);
-            }
-
-            (Some(transform), None) => {
-                let card = spawn_combat_card(
-                    commands,
-                    texture_storage,
-                    transform,
-                    template_storage,
-                    0,
-                );
+    for (slot, label) in piles {
+        let transform = card_slot(slot);
+        sprites.push(spawn_combat_card(commands, texture_storage, &transform, template_storage, 0));
 
-                sprites.push(card);
-            }
-            _ => break
-        }
+        let text_transform = Transform {
+            translation: transform.translation + Vec3::new(0., 0., 105.),
+            scale: Vec3::new(0.01, 0.01, 0.),
+            ..default()
+        };
+        let text = if slot == HAND_SIZE {
+            spawn_text(
+                commands,
+                texture_storage,
+                text_transform,
+                label,
+                "Draw pile text".to_string(),
+                DrawPileText,
+                PlayerMarker,
+            )
+        } else {
+            spawn_text(
+                commands,
+                texture_storage,
+                text_transform,
+                label,
+                "Discard pile text".to_string(),
+                DiscardPileText,
+                PlayerMarker,
+            )
+        };
+        sprites.push(text);
     }
 
     sprites.push(
@@ -992,35 +1036,150 @@ pub fn spawn_bottom_bar(
 }
 
 pub fn init_manager(
-    player_stats_query: Query<&CombatStats, With<Player>>,
+    mut commands: Commands,
+    player_stats_query: Query<(Entity, &CombatStats, &Player)>,
     mut manager: ResMut<CombatManager>,
+    classes: Res<HeroClasses>,
 ) {
-    let player_stats = player_stats_query.single();
-    manager.permanent_damage_buff = player_stats.attack;
-    manager.permanent_defense_buff = player_stats.defense;
-    manager.defense = manager.permanent_defense_buff;
-    manager.damage = player_stats.attack;
-    manager.permanent_mana_buff = player_stats.mana;
-    manager.mana_poll = manager.permanent_mana_buff;
+    let (entity, stats, player) = player_stats_query.single();
+    manager.init(stats);
+    manager.extra_attack_dice = extra_dice(player, DiceKind::Attack);
+    manager.extra_mana_dice = extra_dice(player, DiceKind::Mana);
+
+    let mut statuses = StatusEffects::default();
+    if let ClassPassive::StartingStatus(status) = classes.get(&player.class).passive {
+        statuses.apply(&status);
+    }
+    commands.entity(entity).insert(statuses);
 }
 
 pub fn finalize(
     mut manager: ResMut<CombatManager>,
     mut combat_state: ResMut<State<CombatState>>,
+    mut player_query: Query<(&mut CombatStats, &mut StatusEffects), With<Player>>,
+    mut enemy_query: Query<(&mut CombatStats, &mut StatusEffects), EnemyFilter>,
 ) {
-    manager.damage = manager.permanent_damage_buff;
-    manager.mana_poll = manager.permanent_mana_buff;
-    manager.defense = manager.permanent_defense_buff;
-    manager.can_roll_attack = true;
-    manager.can_roll_mana = true;
-    manager.round += 1;
-    manager.skip_round = false;
-    manager.enemy_skip_round = false;
+    let (mut player_stats, mut player_statuses) = player_query.single_mut();
+
+    player_statuses.round_end();
+    for (_, mut statuses) in enemy_query.iter_mut() {
+        statuses.round_end();
+    }
+
+    manager.expire_buffs();
+    manager.next_round();
+
+    if player_statuses.round_start(&mut player_stats) {
+        manager.player_death = true;
+        combat_state.set(End).unwrap();
+        return;
+    }
+
+    let mut enemies_alive = false;
+    for (mut stats, mut statuses) in enemy_query.iter_mut() {
+        if stats.is_alive() {
+            enemies_alive |= !statuses.round_start(&mut stats);
+        }
+    }
+
+    if !enemies_alive {
+        manager.enemy_death = true;
+        combat_state.set(EnemyDeath).unwrap();
+        return;
+    }
+
+    if player_statuses.stunned {
+        manager.skip_round = true;
+        manager.can_roll_attack = false;
+        manager.can_roll_mana = false;
+    }
 
     combat_state.set(PlayerTurn).unwrap();
     manager.print();
 }
 
+pub fn update_status_icons(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    changed_query: Query<(), Changed<StatusEffects>>,
+    status_query: Query<(&StatusEffects, Option<&Enemy>)>,
+    icon_query: Query<Entity, With<StatusIcon>>,
+) {
+    if changed_query.is_empty() {
+        return;
+    }
+
+    for icon in icon_query.iter() {
+        commands.entity(icon).despawn_recursive();
+    }
+
+    let enemy_count = status_query.iter().filter(|(_, enemy)| enemy.is_some()).count();
+
+    for (statuses, enemy) in status_query.iter() {
+        let origin = match enemy {
+            Some(enemy) => Vec2::new(-2.1 + Enemy::slot_offset(enemy.slot, enemy_count), 0.8),
+            None => Vec2::new(6.3, -2.2),
+        };
+
+        for (i, effect) in statuses.effects.iter().enumerate() {
+            let position = origin + Vec2::new(i as f32 * 0.6, 0.);
+            let icon = spawn_counter_icon(
+                &mut commands,
+                &texture_storage,
+                position,
+                effect.kind.icon(),
+                effect.counter(),
+                format!("{:?} status icon", effect.kind),
+            );
+            commands.entity(icon).insert(StatusIcon);
+        }
+    }
+}
+
+pub fn update_buff_icons(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    manager: Res<CombatManager>,
+    mut shown: Local<Vec<TimedBuff>>,
+    icon_query: Query<Entity, With<BuffIcon>>,
+) {
+    if *shown == manager.timed_buffs {
+        return;
+    }
+
+    for icon in icon_query.iter() {
+        commands.entity(icon).despawn_recursive();
+    }
+
+    for (i, buff) in manager.timed_buffs.iter().enumerate() {
+        let icon = spawn_counter_icon(
+            &mut commands,
+            &texture_storage,
+            Vec2::new(6.3 + i as f32 * 0.6, -1.6),
+            (buff.icon_index(), WHITE),
+            format!("+{}/{}", buff.value, buff.rounds_left),
+            format!("{:?} buff icon", buff.kind),
+        );
+        commands.entity(icon).insert(BuffIcon);
+    }
+
+    *shown = manager.timed_buffs.clone();
+}
+
+pub fn despawn_status_icons(
+    mut commands: Commands,
+    icon_query: Query<Entity, CounterIconFilter>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    for icon in icon_query.iter() {
+        commands.entity(icon).despawn_recursive();
+    }
+
+    for player in player_query.iter() {
+        commands.entity(player).remove::<StatusEffects>();
+    }
+}
+
 pub fn manager_default(mut manager: ResMut<CombatManager>) {
     manager.round = 1;
     manager.damage = 0;
@@ -1033,7 +1192,14 @@ pub fn manager_default(mut manager: ResMut<CombatManager>) {
     manager.can_roll_attack = true;
     manager.skip_round = false;
     manager.enemy_skip_round = false;
+    manager.player_skip_round = false;
+    manager.mana_drain = 0;
     manager.player_death = false;
     manager.enemy_death = false;
     manager.enemy_lvl = 0;
+    manager.acting_enemy = 0;
+    manager.max_health_gain = 0;
+    manager.extra_attack_dice = 0;
+    manager.extra_mana_dice = 0;
+    manager.timed_buffs.clear();
 }
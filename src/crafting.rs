@@ -0,0 +1,404 @@
+use std::fs::File;
+use ron::de::from_reader;
+use serde::Deserialize;
+use crate::prelude::*;
+
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_startup_system_to_stage(StartupStage::PreStartup, Self::load)
+            .add_system_set(
+                SystemSet::on_update(World)
+                    .with_system(open_altar_forge.after(player_movement).before(world_object_event))
+            )
+            .add_system_set(
+                SystemSet::on_enter(Forge)
+                    .with_system(spawn_forge_interface)
+                    .with_system(bag_interface_camera)
+            )
+            .add_system_set(
+                SystemSet::on_update(Forge)
+                    .with_system(forge_action)
+                    .with_system(update_forge_goods.after(forge_action))
+                    .with_system(forge_button_exit)
+            )
+            .add_system_set(
+                SystemSet::on_exit(Forge)
+                    .with_system(despawn_bag_interface)
+                    .with_system(update_stats)
+            );
+    }
+}
+
+impl CraftingPlugin {
+    fn load(mut commands: Commands) {
+        commands.insert_resource(Recipes::load());
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct CombineRecipe {
+    pub slot: SlotKind,
+    pub level: usize,
+    pub result: usize,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpgradeRecipe {
+    pub slot: SlotKind,
+    pub cost: usize,
+    pub step: isize,
+    pub max_value: isize,
+}
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct Recipes {
+    pub combines: Vec<CombineRecipe>,
+    pub upgrades: Vec<UpgradeRecipe>,
+}
+
+impl Recipes {
+    pub fn load() -> Self {
+        let file = File::open("assets/Recipes.ron").expect("Failed opening file");
+        from_reader(file).expect("Unable to load recipes")
+    }
+
+    /// Item the bag item at `index` turns into, if the bag holds a second one of its slot and level.
+    pub fn combine_result(&self, storage: &TemplateStorage, bag: &[ItemView], index: usize) -> Option<usize> {
+        let kind = item_kind(storage, bag.get(index)?.id)?;
+        let recipe = self.combines
+            .iter()
+            .find(|r| r.slot == kind.0 && r.level == kind.1)?;
+
+        combine_pair(storage, bag, index).map(|_| recipe.result)
+    }
+
+    /// Turns the bag item at `index` and its pair into the recipe result, forge upgrades of both
+    /// carried over.
+    pub fn combine(&self, storage: &TemplateStorage, player: &mut Player, index: usize) -> bool {
+        let result = match self.combine_result(storage, &player.items_bag, index) {
+            Some(result) => result,
+            None => return false,
+        };
+        let pair = combine_pair(storage, &player.items_bag, index)
+            .expect("Combined item has no pair in the bag");
+        let bonus = player.items_bag[index].bonus + player.items_bag[pair].bonus;
+
+        // the later entry goes first so the other index still points at its item
+        player.items_bag.remove(std::cmp::max(index, pair));
+        player.items_bag.remove(std::cmp::min(index, pair));
+
+        let level = storage.items
+            .iter()
+            .find(|t| t.id == result)
+            .expect("Recipe result is not an item")
+            .level;
+        player.items_bag.push(ItemView { id: result, level, bonus });
+        true
+    }
+
+    fn upgrade_recipe(&self, index: usize) -> Option<&UpgradeRecipe> {
+        let kind = EQUIPMENT_SLOTS.get(index)?.kind;
+        self.upgrades.iter().find(|r| r.slot == kind)
+    }
+
+    /// Gold the next upgrade of an equipped slot costs, none if the slot is empty or maxed out.
+    /// Upgrades raise the first stat modifier of the worn item.
+    pub fn upgrade_price(&self, build: &ItemBuild, index: usize) -> Option<usize> {
+        let value = build.slot(index)?.modifiers.first()?.value;
+        let recipe = self.upgrade_recipe(index)?;
+
+        if value + recipe.step > recipe.max_value {
+            return None;
+        }
+        Some(recipe.cost * std::cmp::max(value, 1) as usize)
+    }
+
+    pub fn upgrade(&self, player: &mut Player, index: usize) -> bool {
+        let price = match self.upgrade_price(&player.item_build, index) {
+            Some(price) if price <= player.gold => price,
+            _ => return false,
+        };
+        let step = self.upgrade_recipe(index).map_or(0, |r| r.step);
+
+        if let Some(modifier) = player.item_build
+            .slot_mut(index)
+            .and_then(|item| item.modifiers.first_mut())
+        {
+            modifier.value += step;
+        }
+        player.gold -= price;
+        true
+    }
+}
+
+fn item_kind(storage: &TemplateStorage, id: usize) -> Option<(SlotKind, usize)> {
+    storage.items
+        .iter()
+        .find(|t| t.id == id)
+        .and_then(|t| t.item_slot().map(|slot| (slot, t.level)))
+}
+
+/// Another bag entry of the same slot and level as the one at `index`.
+fn combine_pair(storage: &TemplateStorage, bag: &[ItemView], index: usize) -> Option<usize> {
+    let kind = item_kind(storage, bag.get(index)?.id)?;
+    bag
+        .iter()
+        .enumerate()
+        .position(|(i, item)| i != index && item_kind(storage, item.id) == Some(kind))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForgeAction {
+    /// Index in `EQUIPMENT_SLOTS`.
+    Upgrade(usize),
+    /// Index in the bag.
+    Combine(usize),
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct ForgeGoods {
+    pub id: usize,
+    pub action: ForgeAction,
+}
+
+#[derive(Component)]
+pub struct ForgeButton;
+
+#[derive(Component)]
+pub struct ForgeExit;
+
+#[derive(Component)]
+pub struct ForgeGoldText;
+
+#[derive(Component)]
+pub struct ForgeInfoHint;
+
+/// Altars give their reward on the first visit and work as a forge afterwards.
+pub fn open_altar_forge(
+    mut state: ResMut<State<GameState>>,
+    mut last_position: Local<Option<GridPos>>,
+    player_query: Query<(&Transform, &PlayerPath), With<Player>>,
+    event_query: Query<(&Transform, &WorldEvent), WorldEventFilter>,
+) {
+    let (transform, path) = player_query.single();
+    let position = grid_position(transform.translation);
+
+    if !player_arrived(&mut last_position, path, position) {
+        return;
+    }
+
+    let visited_altar = event_query.iter().any(|(t, event)| {
+        event.event_type == WorldEventType::Altar
+            && event.is_visited
+            && grid_position(t.translation) == position
+    });
+
+    if visited_altar {
+        let _ = state.set(Forge);
+    }
+}
+
+pub fn forge_button_exit(
+    selected_query: Query<&Selected, With<ForgeExit>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    let selected = selected_query.single();
+    if selected.selected {
+        state.set(World).expect("Failed to change states");
+    }
+}
+
+pub fn forge_action(
+    goods_query: Query<(&ForgeGoods, &Selected)>,
+    mut player_query: Query<&mut Player>,
+    recipes: Res<Recipes>,
+    template_storage: Res<TemplateStorage>,
+    mut update_event: EventWriter<UpdateEvent>,
+) {
+    let mut player = player_query.single_mut();
+    for (goods, selected) in goods_query.iter() {
+        if !selected.selected {
+            continue;
+        }
+
+        let done = match &goods.action {
+            ForgeAction::Upgrade(index) => recipes.upgrade(&mut player, *index),
+            ForgeAction::Combine(index) => recipes.combine(&template_storage, &mut player, *index),
+        };
+        if done {
+            update_event.send(UpdateEvent(true));
+        }
+    }
+}
+
+pub fn spawn_forge_interface(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    player_query: Query<&Player>,
+    mut update_event: EventWriter<UpdateEvent>,
+) {
+    let player = player_query.single();
+
+    let sprites = vec![
+        spawn_background_element(
+            &mut commands,
+            &texture_storage,
+            Some(Vec2::new(10., 11.5)),
+            Transform::from_xyz(7., -9., 600.),
+            "Forge interface background",
+        ),
+        spawn_world_interface_element(
+            &mut commands,
+            &texture_storage,
+            Transform::from_xyz(11., -4.5, 700.),
+            ForgeExit,
+            Element::Exit,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(6., -4.5, 700.),
+                scale: Vec3::new(0.01, 0.01, 0.),
+                ..default()
+            },
+            format!("Gold {}", player.gold),
+            "Forge gold text".to_string(),
+            ForgeGoldText,
+            PlayerMarker,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -9.1, 700.),
+                scale: Vec3::new(0.01, 0.01, 0.),
+                ..default()
+            },
+            "Combine".to_string(),
+            "Combine text".to_string(),
+            ForgeInfoHint,
+            PlayerMarker,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -14.2, 700.),
+                scale: Vec3::new(0.007, 0.007, 0.),
+                ..default()
+            },
+            "Click an equipped item to upgrade it for gold,\n\
+            a bag item to combine it with another one of its kind and level".to_string(),
+            "Info text".to_string(),
+            ForgeInfoHint,
+            PlayerMarker,
+        ),
+    ];
+
+    update_event.send(UpdateEvent(true));
+
+    let _ = commands
+        .spawn()
+        .insert(Transform::default())
+        .insert(GlobalTransform::default())
+        .insert(Name::new("Forge interface"))
+        .insert(BagInterface)
+        .push_children(&sprites)
+        .id();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_forge_goods(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    template_storage: Res<TemplateStorage>,
+    recipes: Res<Recipes>,
+    player_query: Query<&Player>,
+    mut update_event: EventReader<UpdateEvent>,
+    interface_query: Query<Entity, With<BagInterface>>,
+    goods_query: Query<Entity, With<ForgeGoods>>,
+    mut gold_text_query: Query<&mut Text, With<ForgeGoldText>>,
+) {
+    if update_event.iter().count() == 0 {
+        return;
+    }
+
+    let player = player_query.single();
+    let interface = interface_query.single();
+
+    for entity in goods_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for mut text in gold_text_query.iter_mut() {
+        text.sections[0].value = format!("Gold {}", player.gold);
+    }
+
+    let mut sprites = Vec::new();
+
+    for (index, slot) in EQUIPMENT_SLOTS.iter().enumerate() {
+        let item = match player.item_build.slot(index) {
+            Some(item) => item,
+            None => continue,
+        };
+        let label = match recipes.upgrade_price(&player.item_build, index) {
+            Some(price) => price.to_string(),
+            None => "Max".to_string(),
+        };
+        let (x, y) = slot.position;
+        let goods = ForgeGoods { id: item.id, action: ForgeAction::Upgrade(index) };
+        sprites.push(spawn_forge_goods(
+            &mut commands, &texture_storage, &template_storage, goods, label, Transform::from_xyz(x, y, 700.),
+        ));
+    }
+
+    let bag_positions = [-10., -11.5]
+        .into_iter()
+        .flat_map(|y| (0..6).map(move |i| Transform::from_xyz(3. + 1.5 * i as f32, y, 700.)));
+    for (index, (item, transform)) in player.items_bag.iter().zip(bag_positions).enumerate() {
+        let label = match recipes.combine_result(&template_storage, &player.items_bag, index) {
+            Some(_) => "Combine".to_string(),
+            None => String::new(),
+        };
+        let goods = ForgeGoods { id: item.id, action: ForgeAction::Combine(index) };
+        sprites.push(spawn_forge_goods(
+            &mut commands, &texture_storage, &template_storage, goods, label, transform,
+        ));
+    }
+
+    commands.entity(interface).push_children(&sprites);
+}
+
+fn spawn_forge_goods(
+    commands: &mut Commands,
+    texture_storage: &TextureStorage,
+    template_storage: &TemplateStorage,
+    goods: ForgeGoods,
+    label: String,
+    transform: Transform,
+) -> Entity {
+    let text = spawn_text(
+        commands,
+        texture_storage,
+        Transform {
+            translation: Vec3::new(0., -0.7, 1.),
+            scale: Vec3::new(0.006, 0.006, 0.),
+            ..default()
+        },
+        label,
+        "Forge label".to_string(),
+        PriceText,
+        PlayerMarker,
+    );
+
+    let entity = spawn_bag_item(commands, texture_storage, transform, template_storage, goods.id, true);
+    commands
+        .entity(entity)
+        .insert(goods)
+        .push_children(&[text]);
+    entity
+}
@@ -8,8 +8,7 @@ pub struct DebugPlugin;
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(WorldInspectorPlugin::new())
-            .register_type::<EncounterTracker>()
-            .register_type::<FrameAnimation>()
+            .register_type::<Animator>()
             .register_type::<CombatManager>()
             .register_inspectable::<CombatStats>()
             .register_inspectable::<Player>()
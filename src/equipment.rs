@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use crate::equipment::SlotKind::*;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum SlotKind {
+    Head,
+    Body,
+    Weapon,
+    OffHand,
+    Ring,
+    Amulet,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum Stat {
+    Attack,
+    Defense,
+    Mana,
+    MaxHealth,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub value: isize,
+}
+
+/// Bonus granted while at least `pieces` items of the set are worn.
+/// Several entries with the same name make a tiered set.
+#[derive(Clone, Deserialize, Debug)]
+pub struct ItemSet {
+    pub name: String,
+    pub pieces: usize,
+    pub modifiers: Vec<StatModifier>,
+}
+
+pub struct EquipmentSlot {
+    pub name: &'static str,
+    pub kind: SlotKind,
+    /// Where the slot sits in the bag interface.
+    pub position: (f32, f32),
+}
+
+pub const EQUIPMENT_SLOTS: [EquipmentSlot; 7] = [
+    EquipmentSlot { name: "Head", kind: Head, position: (5., -5.) },
+    EquipmentSlot { name: "Amulet", kind: Amulet, position: (6.5, -5.) },
+    EquipmentSlot { name: "Weapon", kind: Weapon, position: (3.5, -6.5) },
+    EquipmentSlot { name: "Body", kind: Body, position: (5., -6.5) },
+    EquipmentSlot { name: "Off-hand", kind: OffHand, position: (6.5, -6.5) },
+    EquipmentSlot { name: "Ring", kind: Ring, position: (3.5, -8.) },
+    EquipmentSlot { name: "Ring", kind: Ring, position: (5., -8.) },
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquippedItem {
+    pub id: usize,
+    pub set: Option<String>,
+    pub modifiers: Vec<StatModifier>,
+}
+
+impl EquippedItem {
+    pub fn power(&self) -> isize {
+        self.modifiers.iter().map(|m| m.value).sum()
+    }
+}
+
+/// Worn items, one entry per `EQUIPMENT_SLOTS` slot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemBuild {
+    pub slots: Vec<Option<EquippedItem>>,
+}
+
+impl Default for ItemBuild {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; EQUIPMENT_SLOTS.len()],
+        }
+    }
+}
+
+impl ItemBuild {
+    pub fn slot(&self, index: usize) -> Option<&EquippedItem> {
+        self.slots.get(index).and_then(|s| s.as_ref())
+    }
+
+    pub fn slot_mut(&mut self, index: usize) -> Option<&mut EquippedItem> {
+        self.slots.get_mut(index).and_then(|s| s.as_mut())
+    }
+
+    /// Puts the item in a free slot of its kind, or in place of the weakest worn one if it is
+    /// not weaker. Returns the slot index and the replaced item, none if the item was refused.
+    pub fn equip(&mut self, kind: SlotKind, item: EquippedItem) -> Option<(usize, Option<EquippedItem>)> {
+        if self.slots.len() < EQUIPMENT_SLOTS.len() {
+            self.slots.resize(EQUIPMENT_SLOTS.len(), None);
+        }
+
+        let candidates = EQUIPMENT_SLOTS
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.kind == kind)
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+
+        let index = match candidates.iter().find(|i| self.slots[**i].is_none()) {
+            Some(index) => *index,
+            None => *candidates
+                .iter()
+                .min_by_key(|i| self.slot(**i).map_or(0, |s| s.power()))?,
+        };
+
+        if let Some(worn) = self.slot(index) {
+            if worn.power() > item.power() {
+                return None;
+            }
+        }
+
+        let replaced = self.slots[index].replace(item);
+        Some((index, replaced))
+    }
+
+    pub fn unequip(&mut self, index: usize) -> Option<EquippedItem> {
+        self.slots.get_mut(index).and_then(|s| s.take())
+    }
+
+    pub fn active_sets<'a>(&self, sets: &'a [ItemSet]) -> Vec<&'a ItemSet> {
+        sets
+            .iter()
+            .filter(|set| {
+                let worn = self.slots
+                    .iter()
+                    .flatten()
+                    .filter(|item| item.set.as_deref() == Some(set.name.as_str()))
+                    .count();
+                worn >= set.pieces
+            })
+            .collect()
+    }
+
+    /// Sum of a stat over the worn items and the active set bonuses.
+    pub fn total(&self, stat: Stat, sets: &[ItemSet]) -> isize {
+        let items = self.slots
+            .iter()
+            .flatten()
+            .flat_map(|item| item.modifiers.iter());
+        let bonuses = self.active_sets(sets)
+            .into_iter()
+            .flat_map(|set| set.modifiers.iter());
+
+        items
+            .chain(bonuses)
+            .filter(|m| m.stat == stat)
+            .map(|m| m.value)
+            .sum()
+    }
+}
@@ -4,7 +4,7 @@ use std::fmt::Formatter;
 use bevy::ecs::system::EntityCommands;
 use bevy::ui::FocusPolicy;
 use crate::prelude::*;
-use crate::combat::{EnemyType, Selected};
+use crate::combat::Selected;
 use crate::menu::{ButtonActive, UiAssets, UiCameraMarker};
 
 
@@ -16,7 +16,7 @@ impl Plugin for GraphicsPlugin {
             .add_startup_system_to_stage(
                 StartupStage::PreStartup, Self::load_graphics,
             )
-            .add_system(Self::frame_animation);
+            .add_startup_system(load_enemy_sheets);
     }
 }
 
@@ -26,38 +26,16 @@ impl GraphicsPlugin {
         assets: Res<AssetServer>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     ) {
-        let lizard_image = assets.load("units/lizard.png");
-        let lizard_atlas = TextureAtlas::from_grid(lizard_image, Vec2::new(256.0, 256.0), 4, 4);
-        let lizard_atlas_handle = texture_atlases.add(lizard_atlas);
-
-        let medusa_image = assets.load("units/duza.png");
-        let medusa_atlas = TextureAtlas::from_grid(medusa_image, Vec2::new(128.0, 128.0), 4, 5);
-        let medusa_atlas_handle = texture_atlases.add(medusa_atlas);
-
-        let small_dragon_image = assets.load("units/small_dragon.png");
-        let small_dragon_atlas = TextureAtlas::from_grid(small_dragon_image, Vec2::new(128.0, 128.0), 2, 6);
-        let small_dragon_atlas_handle = texture_atlases.add(small_dragon_atlas);
-
-        let gin_image = assets.load("units/djin.png");
-        let gin_atlas = TextureAtlas::from_grid(gin_image, Vec2::new(128.0, 128.0), 8, 2);
-        let gin_atlas_handle = texture_atlases.add(gin_atlas);
-
-        let big_dragon_image = assets.load("units/big_dragon.png");
-        let big_dragon_atlas = TextureAtlas::from_grid(big_dragon_image, Vec2::new(256.0, 256.0), 4, 4);
-        let big_dragon_atlas_handle = texture_atlases.add(big_dragon_atlas);
-
-        let demon_image = assets.load("units/demon.png");
-        let demon_atlas = TextureAtlas::from_grid(demon_image, Vec2::new(256.0, 256.0), 4, 4);
-        let demon_atlas_handle = texture_atlases.add(demon_atlas);
-
         let warlock_skills_image = assets.load("skills/warlock_skills.png");
         let warlock_skills_atlas = TextureAtlas::from_grid(warlock_skills_image, Vec2::new(256.0, 256.0), 4, 4);
-        let warlock_card_handle = texture_atlases.add(warlock_skills_atlas);
+        let card_handle = texture_atlases.add(warlock_skills_atlas);
 
-        let glade = assets.load("interface/battleground1.png");
-        let castle = assets.load("interface/battleground2.png");
-        let forest = assets.load("interface/battleground3.png");
-        let wasteland = assets.load("interface/battleground4.png");
+        let menu_background = BattlegroundSprite {
+            path: "interface/battleground4.png".to_string(),
+            color: (0.75, 0.75, 0.75),
+            flip: true,
+            handle: assets.load("interface/battleground4.png"),
+        };
 
         let combat_bar = assets.load("interface/bar_ready.png");
         let combat_border_frame = assets.load("interface/border_frame.png");
@@ -84,10 +62,6 @@ impl GraphicsPlugin {
         let object_atlas = TextureAtlas::from_grid(object_tiles, Vec2::new(256.0, 256.0), 4, 4);
         let event_object_tiles = texture_atlases.add(object_atlas);
 
-        let hero_idle_image = assets.load("player/hero_idle.png");
-        let hero_idle_atlas = TextureAtlas::from_grid(hero_idle_image, Vec2::new(198.0, 198.0), 5, 4);
-        let hero_idle_atlas_handle = texture_atlases.add(hero_idle_atlas);
-
         let world_interface_image = assets.load("interface/world_interface.png");
         let world_interface_atlas = TextureAtlas::from_grid(world_interface_image, Vec2::new(256.0, 256.0), 2, 3);
         let world_interface_handle = texture_atlases.add(world_interface_atlas);
@@ -99,53 +73,9 @@ impl GraphicsPlugin {
         let menu_off_button_img = assets.load("interface/button_ready_off.png");
         let menu_on_button_img = assets.load("interface/button_ready_on.png");
 
-        commands.insert_resource(FramesSheet {
-            lizard_handle: lizard_atlas_handle,
-            lizard_idle: [13, 14, 15],
-            lizard_attack: [0, 1, 2, 3, 4],
-            lizard_death: [5, 6, 7, 8, 9, 10],
-            lizard_hurt: [11, 12],
-
-            medusa_handle: medusa_atlas_handle,
-            medusa_attack: [0, 1, 2, 3, 4, 5],
-            medusa_death: [6, 7, 8, 9, 10, 11],
-            medusa_hurt: [12, 13],
-            medusa_idle: [14, 15, 16],
-
-            small_dragon_handle: small_dragon_atlas_handle,
-            small_dragon_attack: [0, 1, 2],
-            small_dragon_death: [3, 4, 5, 6],
-            small_dragon_hurt: [7, 8],
-            small_dragon_idle: [9, 10, 11],
-
-            gin_handle: gin_atlas_handle,
-            gin_attack: [0, 1, 2, 3],
-            gin_death: [4, 5, 6, 7, 8, 9],
-            gin_hurt: [10, 11],
-            gin_idle: [12, 13, 14],
-
-            big_dragon_handle: big_dragon_atlas_handle,
-            big_dragon_attack: [0, 1, 2, 3],
-            big_dragon_death: [4, 5, 6, 7, 8],
-            big_dragon_hurt: [9, 10],
-            big_dragon_idle: [11, 12, 13],
-
-            demon_handle: demon_atlas_handle,
-            demon_attack: [0, 1, 2, 3],
-            demon_death: [4, 5, 6, 7, 8, 9],
-            demon_hurt: [10, 11],
-            demon_idle: [12, 13, 14],
-
-            hero_idle_atlas_handle,
-            hero_idle: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
-        });
-
         commands.insert_resource(TextureStorage {
-            warlock_card_handle,
-            glade,
-            castle,
-            forest,
-            wasteland,
+            card_handle,
+            menu_background,
             combat_bar,
             combat_border_frame,
             dice_atlas_handle,
@@ -160,114 +90,51 @@ impl GraphicsPlugin {
             menu_on_button_img
         })
     }
+}
 
-    fn frame_animation(
-        mut sprites_query: Query<(&mut TextureAtlasSprite, &mut FrameAnimation)>,
-        time: Res<Time>,
-    ) {
-        for (mut sprite, mut animation) in sprites_query.iter_mut() {
-            animation.timer.tick(time.delta());
-            if animation.timer.just_finished() {
-                animation.current_frame = (animation.current_frame + 1) % animation.frames.len();
-                sprite.index = animation.frames[animation.current_frame];
-            }
+/// Loads the sheet and battleground of every enemy template.
+pub fn load_enemy_sheets(
+    mut template_storage: ResMut<TemplateStorage>,
+    assets: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    for enemy in template_storage.enemies.iter_mut() {
+        if let Some(sprite) = enemy.sprite.as_mut() {
+            let image = assets.load(sprite.path.as_str());
+            let atlas = TextureAtlas::from_grid(image, Vec2::splat(sprite.tile), sprite.columns, sprite.rows);
+            sprite.handle = texture_atlases.add(atlas);
+            sprite.battleground.handle = assets.load(sprite.battleground.path.as_str());
         }
     }
 }
 
-#[derive(Clone, Copy, Inspectable)]
-pub enum AnimationType {
-    Idle,
-    Attack,
-    Hurt,
-    Death,
-}
-
 pub fn spawn_enemy_sprite(
     commands: &mut Commands,
-    frame_sheet: &FramesSheet,
-    enemy_type: EnemyType,
+    enemy_sprite: &EnemySprite,
+    library: &AnimationLibrary,
+    offset: f32,
 ) -> Entity {
-    let mut sprite = match enemy_type {
-        EnemyType::Lizard => TextureAtlasSprite::new(frame_sheet.lizard_idle[0]),
-        EnemyType::Medusa => TextureAtlasSprite::new(frame_sheet.medusa_idle[0]),
-        EnemyType::SmallDragon => TextureAtlasSprite::new(frame_sheet.small_dragon_idle[0]),
-        EnemyType::Gin => TextureAtlasSprite::new(frame_sheet.gin_idle[0]),
-        EnemyType::BigDragon => TextureAtlasSprite::new(frame_sheet.big_dragon_idle[0]),
-        EnemyType::Demon => TextureAtlasSprite::new(frame_sheet.demon_death[0]),
-    };
-
+    let idle = library
+        .clip(&enemy_sprite.animations, "idle")
+        .expect("Enemy without an idle animation");
+    let mut sprite = TextureAtlasSprite::new(idle.frames[0]);
     sprite.color = Color::rgb(1., 1., 1.);
+    sprite.custom_size = Some(Vec2::new(enemy_sprite.size.0, enemy_sprite.size.1));
 
-    sprite.custom_size = match enemy_type {
-        EnemyType::Medusa => Some(Vec2::new(3., 4.)),
-        EnemyType::Gin => Some(Vec2::new(2.5, 3.2)),
-        EnemyType::SmallDragon => Some(Vec2::new(3., 4.)),
-        _ => Some(Vec2::new(3.5, 5.)),
-    };
-
-    let translation = match enemy_type {
-        EnemyType::Lizard => Vec3::new(-1., 4., 300.0),
-        EnemyType::Medusa => Vec3::new(-1., 4.3, 300.0),
-        EnemyType::SmallDragon => Vec3::new(-1., 4., 300.0),
-        EnemyType::Gin => Vec3::new(-1., 4.3, 300.0),
-        EnemyType::BigDragon => Vec3::new(-1.2, 4.8, 300.0),
-        EnemyType::Demon => Vec3::new(-1., 4.5, 300.0),
-    };
-
-    let animation = match enemy_type {
-        EnemyType::Lizard => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.lizard_idle.to_vec(),
-            current_frame: 0,
-        },
-        EnemyType::Medusa => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.medusa_idle.to_vec(),
-            current_frame: 0,
-        },
-        EnemyType::SmallDragon => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.small_dragon_idle.to_vec(),
-            current_frame: 0,
-        },
-        EnemyType::Gin => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.gin_idle.to_vec(),
-            current_frame: 0,
-        },
-        EnemyType::BigDragon => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.big_dragon_idle.to_vec(),
-            current_frame: 0,
-        },
-        EnemyType::Demon => FrameAnimation {
-            timer: Timer::from_seconds(0.7, true),
-            frames: frame_sheet.demon_idle.to_vec(),
-            current_frame: 0,
-        },
-    };
-
-    let texture_atlas = match enemy_type {
-        EnemyType::Lizard => frame_sheet.lizard_handle.clone(),
-        EnemyType::Medusa => frame_sheet.medusa_handle.clone(),
-        EnemyType::SmallDragon => frame_sheet.small_dragon_handle.clone(),
-        EnemyType::Gin => frame_sheet.gin_handle.clone(),
-        EnemyType::BigDragon => frame_sheet.big_dragon_handle.clone(),
-        EnemyType::Demon => frame_sheet.demon_handle.clone(),
-    };
+    let (x, y) = enemy_sprite.position;
+    let translation = Vec3::new(x + offset, y, 300.0);
 
     commands
         .spawn_bundle(SpriteSheetBundle {
             sprite,
-            texture_atlas,
+            texture_atlas: enemy_sprite.handle.clone(),
             transform: Transform {
                 translation,
                 ..default()
             },
             ..default()
         })
-        .insert(animation)
+        .insert(Animator::new(&enemy_sprite.animations, "idle"))
         .id()
 }
 
@@ -426,7 +293,7 @@ pub fn spawn_reward_button(
     };
 
     let texture_atlas = match reward.entity_type {
-        EntityType::SkillCard => texture_storage.warlock_card_handle.clone(),
+        EntityType::SkillCard => texture_storage.card_handle.clone(),
         _ => texture_storage.items_atlas_handle.clone(),
     };
 
@@ -447,38 +314,20 @@ pub fn spawn_reward_button(
 
 pub fn spawn_combat_battleground(
     commands: &mut Commands,
-    texture_storage: &TextureStorage,
-    enemy_type: &EnemyType,
+    background: &BattlegroundSprite,
     transform: Transform
 ) -> Entity {
-
-    let background = match enemy_type {
-        EnemyType::Lizard | EnemyType::Medusa => texture_storage.forest.clone(),
-        EnemyType::Gin => texture_storage.glade.clone(),
-        EnemyType::BigDragon | EnemyType::SmallDragon => texture_storage.castle.clone(),
-        EnemyType::Demon => texture_storage.wasteland.clone(),
-    };
-
-    let color = match enemy_type {
-        EnemyType::Lizard | EnemyType::Medusa => Color::rgb(0.0, 1.0, 1.0),
-        EnemyType::Gin => Color::rgb(0.7, 1.0, 1.0),
-        EnemyType::BigDragon | EnemyType::SmallDragon => Color::rgb(0.9, 0.9, 0.9),
-        EnemyType::Demon => Color::rgb(0.75, 0.75, 0.75),
-    };
-
-    let flip_x = enemy_type == &EnemyType::SmallDragon
-        || enemy_type == &EnemyType::BigDragon
-        || enemy_type == &EnemyType::Demon;
+    let (r, g, b) = background.color;
 
     commands
         .spawn_bundle(SpriteBundle {
             sprite: Sprite {
-                color,
+                color: Color::rgb(r, g, b),
                 custom_size: Some(Vec2::new(21.5, 12.0)),
-                flip_x,
+                flip_x: background.flip,
                 ..default()
             },
-            texture: background,
+            texture: background.handle.clone(),
             transform,
             ..default()
         })
@@ -512,8 +361,8 @@ pub fn spawn_bag_item(
         lvl: template.level,
         name: template.name.to_string(),
         sprite_index: template.sprite_index,
-        value: template.value.unwrap(),
-        buff_type: template.card_action.as_ref().unwrap().clone(),
+        slot: template.item_slot().expect("Item template without a slot"),
+        modifiers: template.item_modifiers(),
     };
 
     if with_interactive {
@@ -576,6 +425,7 @@ pub fn spawn_combat_card(
             card_action: t.card_action.as_ref().unwrap().clone(),
             sprite_index: t.sprite_index.unwrap(),
             rounds: t.rounds.unwrap(),
+            effects: t.card_effects(),
             ..default()
         };
 
@@ -584,7 +434,7 @@ pub fn spawn_combat_card(
         let spell_icon = commands
             .spawn_bundle(SpriteSheetBundle {
                 sprite: spell_sprite,
-                texture_atlas: texture_storage.warlock_card_handle.clone(),
+                texture_atlas: texture_storage.card_handle.clone(),
                 transform: Transform {
                     translation: Vec3::new(0., 0.5, transform.translation.z),
                     scale: Vec3::new(1.5, 1.5, 100.),
@@ -714,7 +564,7 @@ fn spawn_spell<'w, 's, 'a>(
     commands
         .spawn_bundle(SpriteSheetBundle {
             sprite: spell_sprite,
-            texture_atlas: texture_storage.warlock_card_handle.clone(),
+            texture_atlas: texture_storage.card_handle.clone(),
             transform: *transform,
             ..default()
         })
@@ -763,6 +613,7 @@ pub fn spawn_background_element(
 pub fn spawn_enemy_border_frame(
     commands: &mut Commands,
     texture_storage: &TextureStorage,
+    offset: f32,
 ) -> Entity {
     commands
         .spawn_bundle(SpriteBundle {
@@ -772,7 +623,7 @@ pub fn spawn_enemy_border_frame(
                 ..default()
             },
             texture: texture_storage.combat_bar.clone(),
-            transform: Transform::from_xyz(-1., 2., 101.),
+            transform: Transform::from_xyz(-1. + offset, 2., 101.),
             ..default()
         })
         .insert(GlobalTransform::default())
@@ -805,6 +656,55 @@ pub fn spawn_combat_icon(
         .id()
 }
 
+pub fn spawn_counter_icon(
+    commands: &mut Commands,
+    texture_storage: &TextureStorage,
+    position: Vec2,
+    (index, color): (usize, Color),
+    counter: String,
+    name: String,
+) -> Entity {
+    let text = commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                counter,
+                TextStyle {
+                    font: texture_storage.font.clone(),
+                    font_size: 40.0,
+                    color: Color::GOLD,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform {
+                translation: Vec3::new(0., -0.35, 1.),
+                scale: Vec3::new(0.007, 0.007, 0.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                custom_size: Some(Vec2::new(0.4, 0.4)),
+                index,
+                color,
+                ..default()
+            },
+            texture_atlas: texture_storage.combat_icon_atlas_handle.clone(),
+            transform: Transform::from_xyz(position.x, position.y, 210.),
+            ..default()
+        })
+        .insert(GlobalTransform::default())
+        .insert(Name::new(name))
+        .push_children(&[text])
+        .id()
+}
+
 pub fn spawn_text(
     commands: &mut Commands,
     texture_storage: &TextureStorage,
@@ -839,129 +739,21 @@ pub fn spawn_text(
 }
 
 pub fn spawn_tile(
-    char: char,
+    tile_def: &TileDef,
     texture_storage: &TextureStorage,
     commands: &mut Commands,
     transform: Transform,
     enumerate: usize,
 ) -> Entity {
-    let sprite = match char {
-        '!' => TextureAtlasSprite {
-            index: 0,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'o' => TextureAtlasSprite {
-            index: 1,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        '2' => TextureAtlasSprite {
-            index: 2,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        '3' => TextureAtlasSprite {
-            index: 3,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        '1' => TextureAtlasSprite {
-            index: 4,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-
-        'a' => TextureAtlasSprite {
-            index: 0,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'D' => TextureAtlasSprite {
-            index: 1,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'C' => TextureAtlasSprite {
-            index: 2,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'c' => TextureAtlasSprite {
-            index: 3,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        '(' => TextureAtlasSprite {
-            index: 4,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'F' => TextureAtlasSprite {
-            index: 5,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        's' => TextureAtlasSprite {
-            index: 6,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'f' => TextureAtlasSprite {
-            index: 7,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'm' => TextureAtlasSprite {
-            index: 8,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'M' => TextureAtlasSprite {
-            index: 9,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'p' => TextureAtlasSprite {
-            index: 10,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'r' => TextureAtlasSprite {
-            index: 11,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'S' => TextureAtlasSprite {
-            index: 12,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'd' => TextureAtlasSprite {
-            index: 13,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        'j' => TextureAtlasSprite {
-            index: 14,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        't' => TextureAtlasSprite {
-            index: 15,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
-        _ => TextureAtlasSprite {
-            index: 0,
-            custom_size: Some(Vec2::new(1., 1.)),
-            ..default()
-        },
+    let sprite = TextureAtlasSprite {
+        index: tile_def.sprite_index,
+        custom_size: Some(Vec2::new(1., 1.)),
+        ..default()
     };
 
-    let texture_atlas = match char {
-        '!' | 'o' | '1' | '2' | '3' => texture_storage.base_tiles.clone(),
-        _ => texture_storage.event_object_tiles.clone()
+    let texture_atlas = match tile_def.atlas {
+        TileAtlas::Base => texture_storage.base_tiles.clone(),
+        TileAtlas::EventObjects => texture_storage.event_object_tiles.clone()
     };
 
     let tile = commands.spawn_bundle(SpriteSheetBundle {
@@ -971,94 +763,94 @@ pub fn spawn_tile(
         ..default()
     })
         .insert(Tile(enumerate))
+        .insert(Name::new(tile_def.name.clone()))
         .id();
 
-    match char {
-        '1' | '2' | '3' => {
-            commands.entity(tile)
-                .insert(Name::new("Collider"))
-                .insert(TileCollider);
-        }
-        'm' | 'M' | 'd' | 'j' | 's' | 'S' | 'p' | 'D' => {
+    if tile_def.collider {
+        commands.entity(tile).insert(TileCollider);
+        return tile;
+    }
+
+    match &tile_def.kind {
+        TileKind::Encounter(enemy_types) => {
             commands.entity(tile)
                 .insert(EncounterSpawner)
-                .insert(EncounterType(
-                    EnemyType::get_type(char), false)
-                )
-                .insert(Name::new("EncounterPoint"))
-                .insert(Point(transform));
-        }
-        'c' => {
-            commands.entity(tile)
-                .insert(WorldEventMarker)
-                .insert(WorldEvent {
-                    event_type: WorldEventType::Camp,
-                    lvl: 1,
-                    is_visited: false,
-                })
-                .insert(Name::new("Small camp"))
-                .insert(Point(transform));
-        }
-        'C' => {
-            commands.entity(tile)
-                .insert(WorldEventMarker)
-                .insert(WorldEvent {
-                    event_type: WorldEventType::Camp,
-                    lvl: 2,
-                    is_visited: false,
-                })
-                .insert(Name::new("Middle camp"))
-                .insert(Point(transform));
-        }
-        '(' => {
-            commands.entity(tile)
-                .insert(WorldEventMarker)
-                .insert(WorldEvent {
-                    event_type: WorldEventType::Camp,
-                    lvl: 3,
-                    is_visited: false,
-                })
-                .insert(Name::new("Big camp"))
-                .insert(Point(transform));
+                .insert(EncounterType(enemy_types.clone(), false));
         }
-        'r' => {
+        TileKind::Event { event_type, lvl } => {
             commands.entity(tile)
                 .insert(WorldEventMarker)
                 .insert(WorldEvent {
-                    event_type: WorldEventType::Ruins,
-                    lvl: 0,
+                    event_type: *event_type,
+                    lvl: *lvl,
                     is_visited: false,
-                })
-                .insert(Name::new("Ruins"))
-                .insert(Point(transform));
-        }
-        'a' => {
-            commands.entity(tile)
-                .insert(WorldEventMarker)
-                .insert(WorldEvent {
-                    event_type: WorldEventType::Altar,
-                    lvl: 0,
-                    is_visited: false,
-                })
-                .insert(Name::new("Altar"))
-                .insert(Point(transform));
+                });
         }
-        '!' | 't' => {
-            commands.entity(tile)
-                .insert(Town)
-                .insert(Name::new("Town"))
-                .insert(Point(transform));
+        TileKind::Town => {
+            commands.entity(tile).insert(Town);
         }
-        _ => {
-            commands.entity(tile)
-                .insert(Name::new("Point"))
-                .insert(Point(transform));
+        TileKind::Lair { enemy_type, sprite_index } => {
+            commands.entity(tile).insert(Lair {
+                enemy_type: enemy_type.clone(),
+                sprite_index: *sprite_index,
+            });
         }
+        TileKind::Plain => {}
     };
 
+    commands.entity(tile).insert(Point(transform));
+
     tile
 }
 
+pub fn spawn_roamer(
+    commands: &mut Commands,
+    texture_storage: &TextureStorage,
+    roamer: Roamer,
+    pos: GridPos,
+) -> Entity {
+    let sprite = TextureAtlasSprite {
+        index: roamer.sprite_index,
+        custom_size: Some(Vec2::new(1., 1.)),
+        ..default()
+    };
+
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            sprite,
+            texture_atlas: texture_storage.event_object_tiles.clone(),
+            transform: Transform::from_xyz(pos.0 as f32, pos.1 as f32, 400.),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(Name::new(format!("Roaming {}", roamer.enemy_type.0)))
+        .insert(EncounterSpawner)
+        .insert(EncounterType(vec![roamer.enemy_type.clone()], false))
+        .insert(roamer)
+        .id()
+}
+
+pub fn spawn_tile_marker(
+    commands: &mut Commands,
+    pos: GridPos,
+    color: Color,
+    z: f32,
+    marker: impl Component,
+) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(TILE_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(pos.0 as f32, pos.1 as f32, z),
+            ..default()
+        })
+        .insert(marker)
+        .id()
+}
+
 pub fn spawn_menu_button<T: Component>(
     texture_storage: &TextureStorage,
     commands: &mut Commands,
@@ -1122,53 +914,11 @@ pub fn spawn_menu_button<T: Component>(
         .insert_resource(ui_assets);
 }
 
-pub struct FramesSheet {
-    pub lizard_handle: Handle<TextureAtlas>,
-    pub lizard_idle: [usize; 3],
-    pub lizard_attack: [usize; 5],
-    pub lizard_hurt: [usize; 2],
-    pub lizard_death: [usize; 6],
-
-    pub medusa_handle: Handle<TextureAtlas>,
-    pub medusa_idle: [usize; 3],
-    pub medusa_attack: [usize; 6],
-    pub medusa_hurt: [usize; 2],
-    pub medusa_death: [usize; 6],
-
-    pub small_dragon_handle: Handle<TextureAtlas>,
-    pub small_dragon_attack: [usize; 3],
-    pub small_dragon_death: [usize; 4],
-    pub small_dragon_hurt: [usize; 2],
-    pub small_dragon_idle: [usize; 3],
-
-    pub gin_handle: Handle<TextureAtlas>,
-    pub gin_attack: [usize; 4],
-    pub gin_death: [usize; 6],
-    pub gin_hurt: [usize; 2],
-    pub gin_idle: [usize; 3],
-
-    pub big_dragon_handle: Handle<TextureAtlas>,
-    pub big_dragon_attack: [usize; 4],
-    pub big_dragon_death: [usize; 5],
-    pub big_dragon_hurt: [usize; 2],
-    pub big_dragon_idle: [usize; 3],
-
-    pub demon_handle: Handle<TextureAtlas>,
-    pub demon_attack: [usize; 4],
-    pub demon_death: [usize; 6],
-    pub demon_hurt: [usize; 2],
-    pub demon_idle: [usize; 3],
-
-    pub hero_idle_atlas_handle: Handle<TextureAtlas>,
-    pub hero_idle: [usize; 16],
-}
-
 pub struct TextureStorage {
-    pub warlock_card_handle: Handle<TextureAtlas>,
-    pub glade: Handle<Image>,
-    pub castle: Handle<Image>,
-    pub forest: Handle<Image>,
-    pub wasteland: Handle<Image>,
+    /// Skill card sheet of the player's class.
+    pub card_handle: Handle<TextureAtlas>,
+    /// Battleground behind the menus.
+    pub menu_background: BattlegroundSprite,
     pub combat_bar: Handle<Image>,
     pub combat_border_frame: Handle<Image>,
     pub dice_atlas_handle: Handle<TextureAtlas>,
@@ -1183,10 +933,3 @@ pub struct TextureStorage {
     pub menu_on_button_img: Handle<Image>
 }
 
-#[derive(Component, Reflect, Default)]
-#[reflect(Component)]
-pub struct FrameAnimation {
-    pub timer: Timer,
-    pub frames: Vec<usize>,
-    pub current_frame: usize,
-}
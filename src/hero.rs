@@ -0,0 +1,170 @@
+use std::fs::File;
+use ron::de::from_reader;
+use serde::Deserialize;
+use crate::prelude::*;
+
+pub struct HeroPlugin;
+
+impl Plugin for HeroPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_startup_system_to_stage(StartupStage::PreStartup, Self::load)
+            .add_event::<ClassEvent>()
+            .add_startup_system(load_class_sheets)
+            .add_system(replay_class)
+            .add_system(choose_class.after(replay_class))
+            .add_system_set(
+                SystemSet::on_update(World)
+                    .with_system(apply_class_sheets)
+            );
+    }
+}
+
+impl HeroPlugin {
+    fn load(mut commands: Commands) {
+        commands.insert_resource(HeroClasses::load());
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub enum ClassPassive {
+    /// Health restored after every won fight.
+    VictoryHeal(isize),
+    /// Status the hero starts every fight with.
+    StartingStatus(StatusEffect),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct HeroSprite {
+    pub path: String,
+    pub tile: f32,
+    pub columns: usize,
+    pub rows: usize,
+    /// Set in `assets/Animations.ron`, with an idle state.
+    pub animations: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct HeroClass {
+    pub name: String,
+    pub description: String,
+    pub stats: CombatStats,
+    pub deck: Vec<usize>,
+    /// Skill cards this class can find or buy.
+    pub cards: Vec<usize>,
+    pub sprite: HeroSprite,
+    pub card_sheet: String,
+    pub passive: ClassPassive,
+    #[serde(skip)]
+    pub sprite_handle: Handle<TextureAtlas>,
+    #[serde(skip)]
+    pub card_handle: Handle<TextureAtlas>,
+}
+
+impl HeroClass {
+    /// Resets the player to the class's starting stats and deck.
+    pub fn apply(&self, player: &mut Player, stats: &mut CombatStats, storage: &TemplateStorage) {
+        player.class = self.name.clone();
+        player.base_stats = self.stats.clone();
+        player.deck.clear();
+        player.combat_deck.clear();
+
+        for id in self.deck.iter() {
+            let level = storage.skill_cards
+                .iter()
+                .find(|t| t.id == *id)
+                .expect("Unknown starting card")
+                .level;
+            player.add_in_deck(*id, level);
+        }
+
+        player.derive_stats(stats, &storage.sets);
+        stats.health = stats.max_health;
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct HeroClasses(pub Vec<HeroClass>);
+
+impl HeroClasses {
+    pub fn load() -> Self {
+        let file = File::open("assets/Classes.ron").expect("Failed opening file");
+        from_reader(file).expect("Unable to load hero classes")
+    }
+
+    /// Class by name, the first one for saves made before classes existed.
+    pub fn get(&self, name: &str) -> &HeroClass {
+        self.0
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| self.0.first().expect("No hero classes"))
+    }
+}
+
+pub fn load_class_sheets(
+    mut classes: ResMut<HeroClasses>,
+    assets: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    for class in classes.0.iter_mut() {
+        let sprite = &class.sprite;
+        let image = assets.load(sprite.path.as_str());
+        let atlas = TextureAtlas::from_grid(image, Vec2::splat(sprite.tile), sprite.columns, sprite.rows);
+        class.sprite_handle = texture_atlases.add(atlas);
+
+        let image = assets.load(class.card_sheet.as_str());
+        let atlas = TextureAtlas::from_grid(image, Vec2::new(256.0, 256.0), 4, 4);
+        class.card_handle = texture_atlases.add(atlas);
+    }
+}
+
+/// Swaps in the hero and card sheets of the player's class once it is chosen or loaded.
+pub fn apply_class_sheets(
+    mut applied: Local<Option<String>>,
+    classes: Res<HeroClasses>,
+    mut texture_storage: ResMut<TextureStorage>,
+    mut player_query: Query<(&Player, &mut Handle<TextureAtlas>, &mut Animator)>,
+) {
+    let (player, mut handle, mut animator) = player_query.single_mut();
+    if applied.as_deref() == Some(player.class.as_str()) {
+        return;
+    }
+
+    let class = classes.get(&player.class);
+    *handle = class.sprite_handle.clone();
+    texture_storage.card_handle = class.card_handle.clone();
+    *animator = Animator::new(&class.sprite.animations, "idle");
+
+    *applied = Some(player.class.clone());
+}
+
+/// Class picked on the class-select screen, as an index in `HeroClasses`.
+pub struct ClassEvent(pub usize);
+
+pub fn replay_class(mut replay: ResMut<Replay>, mut class_event: EventWriter<ClassEvent>) {
+    if let Some(index) = replay.class.take() {
+        class_event.send(ClassEvent(index));
+    }
+}
+
+pub fn choose_class(
+    mut class_event: EventReader<ClassEvent>,
+    mut player_query: Query<(&mut Player, &mut CombatStats)>,
+    mut shop: ResMut<ShopStock>,
+    mut rng: ResMut<GameRng>,
+    classes: Res<HeroClasses>,
+    template_storage: Res<TemplateStorage>,
+) {
+    for event in class_event.iter() {
+        let class = &classes.0[event.0];
+        let (mut player, mut stats) = player_query.single_mut();
+        class.apply(&mut player, &mut stats, &template_storage);
+        shop.reset(&template_storage, &class.cards, &mut rng);
+        println!("Playing as {}", class.name);
+    }
+}
+
+/// Card pool of the player's class, items are always allowed.
+pub fn class_allows(storage: &TemplateStorage, pool: &[usize], id: usize) -> bool {
+    storage.items.iter().any(|t| t.id == id) || pool.contains(&id)
+}
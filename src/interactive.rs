@@ -37,6 +37,22 @@ impl Plugin for InteractivePlugin {
                     .with_system(spell)
                     .with_system(hint)
                     .with_system(skill_pack_exit_button)
+            )
+            .add_system_set(
+                SystemSet::on_update(Shop)
+                    .with_system(shop_goods)
+                    .with_system(hint)
+                    .with_system(shop_exit_button)
+                    .with_system(forge_button)
+            )
+            .add_system_set(
+                SystemSet::on_update(Forge)
+                    .with_system(forge_goods)
+                    .with_system(forge_exit_button)
+            )
+            .add_system_set(
+                SystemSet::on_update(LevelUp)
+                    .with_system(talent_button)
             );
     }
 }
@@ -45,6 +61,15 @@ impl Plugin for InteractivePlugin {
 #[derive(Component, Inspectable, Default)]
 pub struct Interactive;
 
+/// Sprite buttons that light up under the cursor.
+type ButtonQuery<'w, 's, 'a, T> =
+    Query<'w, 's, (&'a Transform, &'a mut TextureAtlasSprite, &'a T), (With<Interactive>, With<T>)>;
+/// Bag, shop, forge and talent entries picked with a click.
+type BagElementQuery<'w, 's, 'a, T> =
+    Query<'w, 's, (&'a Transform, &'a mut TextureAtlasSprite, &'a mut Selected), (With<T>, With<Interactive>)>;
+type SkillCardQuery<'w, 's, 'a> =
+    Query<'w, 's, (&'a Transform, &'a mut Sprite, &'a mut Selected), (With<Card>, With<Interactive>)>;
+
 #[derive(Component)]
 pub struct Hint;
 
@@ -62,7 +87,12 @@ pub fn cursor_position(
     windows: Res<Windows>,
     mut cursor_state: ResMut<CursorState>,
     buttons: Res<Input<MouseButton>>,
+    replay: Res<Replay>,
 ) {
+    if replay.is_playing() {
+        return;
+    }
+
     let (camera, camera_transform) = camera_query.single();
     let window = windows.get_primary().unwrap();
 
@@ -99,8 +129,7 @@ pub fn cursor_position(
 
 fn attack_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &AttackButton), (With<Interactive>, With<AttackButton>)>,
+    item_position: ButtonQuery<AttackButton>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<AttackButton>>,
 ) {
@@ -109,8 +138,7 @@ fn attack_button(
 
 fn hero_spell_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &HeroSpellButton), (With<Interactive>, With<HeroSpellButton>)>,
+    item_position: ButtonQuery<HeroSpellButton>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<HeroSpellButton>>,
 ) {
@@ -119,8 +147,7 @@ fn hero_spell_button(
 
 fn combat_end_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &CombatEndButton), (With<Interactive>, With<CombatEndButton>)>,
+    item_position: ButtonQuery<CombatEndButton>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<CombatEndButton>>,
 ) {
@@ -129,8 +156,7 @@ fn combat_end_button(
 
 fn attack_dice(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &AttackDice), (With<Interactive>, With<AttackDice>)>,
+    item_position: ButtonQuery<AttackDice>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<AttackDice>>,
 ) {
@@ -139,8 +165,7 @@ fn attack_dice(
 
 fn mana_dice(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &ManaDice), (With<Interactive>, With<ManaDice>)>,
+    item_position: ButtonQuery<ManaDice>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<ManaDice>>,
 ) {
@@ -149,8 +174,7 @@ fn mana_dice(
 
 fn move_dice_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &MoveDice), (With<Interactive>, With<MoveDice>)>,
+    item_position: ButtonQuery<MoveDice>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<MoveDice>>,
 ) {
@@ -159,8 +183,7 @@ fn move_dice_button(
 
 fn back_pack_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &Backpack), (With<Interactive>, With<Backpack>)>,
+    item_position: ButtonQuery<Backpack>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<Backpack>>,
 ) {
@@ -169,8 +192,7 @@ fn back_pack_button(
 
 fn back_pack_exit_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &BagExit), (With<Interactive>, With<BagExit>)>,
+    item_position: ButtonQuery<BagExit>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<BagExit>>,
 ) {
@@ -179,18 +201,43 @@ fn back_pack_exit_button(
 
 fn skill_pack_exit_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &SkillPackExit), (With<Interactive>, With<SkillPackExit>)>,
+    item_position: ButtonQuery<SkillPackExit>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<SkillPackExit>>,
 ) {
     combat_element_event(cursor_state, item_position, WHITE, buttons, selected_query);
 }
 
+fn shop_exit_button(
+    cursor_state: Res<CursorState>,
+    item_position: ButtonQuery<ShopExit>,
+    buttons: ResMut<Input<MouseButton>>,
+    selected_query: Query<&mut Selected, With<ShopExit>>,
+) {
+    combat_element_event(cursor_state, item_position, WHITE, buttons, selected_query);
+}
+
+fn forge_button(
+    cursor_state: Res<CursorState>,
+    item_position: ButtonQuery<ForgeButton>,
+    buttons: ResMut<Input<MouseButton>>,
+    selected_query: Query<&mut Selected, With<ForgeButton>>,
+) {
+    combat_element_event(cursor_state, item_position, WHITE, buttons, selected_query);
+}
+
+fn forge_exit_button(
+    cursor_state: Res<CursorState>,
+    item_position: ButtonQuery<ForgeExit>,
+    buttons: ResMut<Input<MouseButton>>,
+    selected_query: Query<&mut Selected, With<ForgeExit>>,
+) {
+    combat_element_event(cursor_state, item_position, WHITE, buttons, selected_query);
+}
+
 fn accept_reward_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &AcceptRewardButton), (With<Interactive>, With<AcceptRewardButton>)>,
+    item_position: ButtonQuery<AcceptRewardButton>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<AcceptRewardButton>>,
 ) {
@@ -199,8 +246,7 @@ fn accept_reward_button(
 
 fn skill_pack_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &SkillPack), (With<Interactive>, With<SkillPack>)>,
+    item_position: ButtonQuery<SkillPack>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<SkillPack>>,
 ) {
@@ -209,8 +255,7 @@ fn skill_pack_button(
 
 fn next_button(
     cursor_state: Res<CursorState>,
-    item_position:
-    Query<(&Transform, &mut TextureAtlasSprite, &NextButton), (With<Interactive>, With<NextButton>)>,
+    item_position: ButtonQuery<NextButton>,
     buttons: ResMut<Input<MouseButton>>,
     selected_query: Query<&mut Selected, With<NextButton>>,
 ) {
@@ -219,7 +264,7 @@ fn next_button(
 
 fn spell(
     cursor_state: Res<CursorState>,
-    item_position: Query<(&Transform, &mut TextureAtlasSprite, &mut Selected), (With<CardView>, With<Interactive>)>,
+    item_position: BagElementQuery<CardView>,
     buttons: Res<Input<MouseButton>>,
 ) {
     bag_element(cursor_state, item_position, buttons);
@@ -227,7 +272,31 @@ fn spell(
 
 fn item(
     cursor_state: Res<CursorState>,
-    item_position: Query<(&Transform, &mut TextureAtlasSprite, &mut Selected), (With<Item>, With<Interactive>)>,
+    item_position: BagElementQuery<Item>,
+    buttons: Res<Input<MouseButton>>,
+) {
+    bag_element(cursor_state, item_position, buttons);
+}
+
+fn shop_goods(
+    cursor_state: Res<CursorState>,
+    item_position: BagElementQuery<ShopGoods>,
+    buttons: Res<Input<MouseButton>>,
+) {
+    bag_element(cursor_state, item_position, buttons);
+}
+
+fn forge_goods(
+    cursor_state: Res<CursorState>,
+    item_position: BagElementQuery<ForgeGoods>,
+    buttons: Res<Input<MouseButton>>,
+) {
+    bag_element(cursor_state, item_position, buttons);
+}
+
+fn talent_button(
+    cursor_state: Res<CursorState>,
+    item_position: BagElementQuery<TalentChoice>,
     buttons: Res<Input<MouseButton>>,
 ) {
     bag_element(cursor_state, item_position, buttons);
@@ -235,7 +304,7 @@ fn item(
 
 fn bag_element<T: Component>(
     cursor_state: Res<CursorState>,
-    mut item_position: Query<(&Transform, &mut TextureAtlasSprite, &mut Selected), (With<T>, With<Interactive>)>,
+    mut item_position: BagElementQuery<T>,
     buttons: Res<Input<MouseButton>>,
 ) {
     for (transform, mut sprite, mut selected) in item_position.iter_mut() {
@@ -272,7 +341,7 @@ fn hint(
 
 fn combat_skill_card_event(
     cursor_state: Res<CursorState>,
-    mut item_position: Query<(&Transform, &mut Sprite, &mut Selected), (With<Card>, With<Interactive>)>,
+    mut item_position: SkillCardQuery,
     buttons: Res<Input<MouseButton>>,
 ) {
     for (transform, mut sprite, mut selected) in item_position.iter_mut() {
@@ -302,7 +371,7 @@ fn combat_skill_card_event(
 
 fn combat_element_event<T: Component>(
     cursor_state: Res<CursorState>,
-    mut item_position: Query<(&Transform, &mut TextureAtlasSprite, &T), (With<Interactive>, With<T>)>,
+    mut item_position: ButtonQuery<T>,
     base_element_color: Color,
     buttons: ResMut<Input<MouseButton>>,
     mut selected_query: Query<&mut Selected, With<T>>,
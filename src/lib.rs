@@ -0,0 +1,140 @@
+use bevy::app::PluginGroupBuilder;
+use prelude::*;
+
+mod player;
+mod debug;
+mod map;
+mod mapgen;
+mod camera;
+mod interactive;
+mod combat;
+mod graphics;
+mod template;
+mod world;
+mod menu;
+mod save;
+mod rng;
+mod replay;
+mod pathfinding;
+mod roaming;
+mod shop;
+mod crafting;
+mod equipment;
+mod progression;
+mod hero;
+mod animation;
+mod validation;
+mod simulation;
+
+
+pub mod prelude {
+    pub extern crate core;
+    use std::ops::Range;
+    pub use bevy::prelude::*;
+    pub use bevy::window::{PresentMode, WindowMode::*};
+    pub use bevy::render::camera::*;
+    pub use bevy::core::*;
+    pub use bevy_inspector_egui::Inspectable;
+    pub use rand::prelude::*;
+
+    pub use crate::debug::*;
+    pub use crate::map::*;
+    pub use crate::mapgen::*;
+    pub use crate::camera::CameraPlugin;
+    pub use crate::interactive::*;
+    pub use crate::GamePlugins;
+    pub use crate::GameState::{Menu, World, Combat, BagPack, Deck, Shop, Forge, LevelUp, ClassSelect};
+    pub use crate::combat::*;
+    pub use crate::graphics::*;
+    pub use crate::template::*;
+    pub use crate::world::*;
+    pub use crate::player::*;
+    pub use crate::menu::*;
+    pub use crate::save::*;
+    pub use crate::rng::*;
+    pub use crate::replay::*;
+    pub use crate::pathfinding::*;
+    pub use crate::roaming::*;
+    pub use crate::shop::*;
+    pub use crate::crafting::*;
+    pub use crate::equipment::*;
+    pub use crate::progression::*;
+    pub use crate::hero::*;
+    pub use crate::animation::*;
+    pub use crate::validation::*;
+    pub use crate::simulation::*;
+
+    pub const SILVER: Color = Color::rgb(0.75, 0.75, 0.75);
+    pub const RGB: f32 = 255.0;
+    pub const RESOLUTION: f32 = 16.0 / 9.0;
+    pub const SCREEN_HEIGHT: f32 = 1080.;
+    pub const CAMERA_SCALE: f32 = 6.0;
+    pub const CAMERA_MOVE_BORDER: Range<f32> = 0. .. 25.;
+    pub const CAMERA_SPEED: f32 = 7.0;
+    pub const TILE_SIZE: f32 = 1.;
+    pub const HOVER_COLOR: Color = Color::rgb(0.18, 0.55, 0.34);
+    pub const COMBAT_INTERFACE_COLOR: Color = Color::rgb(208. / RGB, 253. / RGB, 255. / RGB);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const REACHABLE_COLOR: Color = Color::rgba(0.18, 0.55, 0.34, 0.25);
+    pub const PATH_COLOR: Color = Color::rgba(0.18, 0.55, 0.34, 0.6);
+    pub const FOG_COLOR: Color = Color::rgb(0.45, 0.45, 0.45);
+    pub const SIGHT_RADIUS: usize = 3;
+    pub const UNTARGETED_COLOR: Color = Color::rgb(0.6, 0.6, 0.6);
+    pub const PATH_OUT_OF_REACH_COLOR: Color = Color::rgba(0.75, 0.2, 0.2, 0.6);
+    pub const POISON_COLOR: Color = Color::rgb(0.7, 0.3, 0.9);
+    pub const BURN_COLOR: Color = Color::rgb(1.0, 0.5, 0.1);
+    pub const STUN_COLOR: Color = Color::rgb(0.9, 0.9, 0.3);
+    pub const SHIELD_COLOR: Color = Color::rgb(0.4, 0.7, 1.0);
+
+    pub type WorldEventFilter = (With<WorldEventMarker>, Without<Player>);
+    pub type SpawnerFilter = (With<EncounterSpawner>, Without<Player>);
+    pub type UiCameraFilter = (Without<Player>, Without<UiCameraMarker>, With<Camera>);
+    pub type CameraFilter = (With<Camera>, Without<UiCameraMarker>);
+    pub type TileFilter = (With<Tile>, Without<Player>);
+    pub type TargetFilter = (With<Enemy>, With<Target>, Without<Player>);
+    pub type EnemyFilter = (With<Enemy>, Without<Player>);
+    pub type PathMarkerFilter = Or<(With<ReachableMarker>, With<PathMarker>)>;
+    pub type CounterIconFilter = Or<(With<StatusIcon>, With<BuffIcon>)>;
+
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+pub enum GameState {
+    Menu,
+    World,
+    Combat,
+    BagPack,
+    Deck,
+    Shop,
+    Forge,
+    LevelUp,
+    ClassSelect,
+}
+
+/// Every plugin of the game, shared by the windowed game and the headless replay runner.
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(&mut self, group: &mut PluginGroupBuilder) {
+        group
+            .add(RngPlugin)
+            .add(InteractivePlugin)
+            .add(CameraPlugin)
+            .add(GraphicsPlugin)
+            .add(SpriteAnimationPlugin)
+            .add(TemplatePlugin)
+            .add(PlayerPlugin)
+            .add(CombatPlugin)
+            .add(DebugPlugin)
+            .add(MapPlugin)
+            .add(WorldPlugin)
+            .add(RoamingPlugin)
+            .add(ShopPlugin)
+            .add(CraftingPlugin)
+            .add(ProgressionPlugin)
+            .add(HeroPlugin)
+            .add(MenuPlugin)
+            .add(SavePlugin)
+            .add(ReplayPlugin);
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
 use crate::prelude::*;
 
 pub struct MapPlugin;
@@ -7,8 +10,12 @@ pub struct MapPlugin;
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<MapSettings>()
+            .init_resource::<MapGrid>()
+            .init_resource::<FogOfWar>()
             .add_startup_system(create_map)
             .add_system_set(SystemSet::on_enter(World).with_system(show_map))
+            .add_system_set(SystemSet::on_update(World).with_system(update_fog.after(player_walk)))
             .add_system_set(SystemSet::on_exit(World).with_system(hide_map));
     }
 }
@@ -20,7 +27,7 @@ pub struct TileCollider;
 pub struct EncounterSpawner;
 
 #[derive(Component)]
-pub struct EncounterType(pub EnemyType, pub bool);
+pub struct EncounterType(pub Vec<EnemyType>, pub bool);
 
 #[derive(Component)]
 pub struct Map;
@@ -31,6 +38,170 @@ pub struct Point(pub Transform);
 #[derive(Component, Inspectable, Default)]
 pub struct Tile(pub usize);
 
+/// Name of the map in `assets/map` to play, `WARLOCK_MAP` overrides the default one.
+pub struct MapSettings {
+    pub name: String,
+}
+
+impl MapSettings {
+    /// Seed to generate the map from, `None` for a map file.
+    pub fn generated_seed(&self, run_seed: u64) -> Option<u64> {
+        if self.name == GENERATED_MAP {
+            return Some(run_seed);
+        }
+        self.name
+            .strip_prefix(GENERATED_MAP)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .map(|seed| seed.parse().unwrap_or_else(|_| panic!("Bad map seed in {}", self.name)))
+    }
+
+    /// Loads the map or generates it, naming a generated one after its seed so saves
+    /// and replays come back to the same map.
+    pub fn map_file(&mut self, run_seed: u64) -> MapFile {
+        match self.generated_seed(run_seed) {
+            Some(seed) => {
+                self.name = format!("{}-{}", GENERATED_MAP, seed);
+                MapGenerator::load().generate(seed)
+            }
+            None => MapFile::load(&self.name),
+        }
+    }
+}
+
+impl Default for MapSettings {
+    fn default() -> Self {
+        Self {
+            name: std::env::var("WARLOCK_MAP").unwrap_or_else(|_| "default".to_string()),
+        }
+    }
+}
+
+/// Grid positions of the tiles the player can stand on.
+#[derive(Default)]
+pub struct MapGrid {
+    pub walkable: HashSet<GridPos>,
+}
+
+/// Tiles the player has seen and the ones in sight right now.
+#[derive(Default)]
+pub struct FogOfWar {
+    pub explored: HashSet<GridPos>,
+    pub visible: HashSet<GridPos>,
+    pub sight_center: Option<GridPos>,
+}
+
+impl FogOfWar {
+    pub fn reveal(&mut self, center: GridPos) {
+        let radius = SIGHT_RADIUS as i32;
+        self.sight_center = Some(center);
+        self.visible.clear();
+
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                if dx * dx + dy * dy <= radius * radius {
+                    self.visible.insert((center.0 + dx, center.1 + dy));
+                }
+            }
+        }
+
+        self.explored.extend(self.visible.iter());
+    }
+
+    pub fn known_walkable(&self, grid: &MapGrid) -> HashSet<GridPos> {
+        grid.walkable.intersection(&self.explored).copied().collect()
+    }
+
+    fn apply(&self, pos: GridPos, sprite: &mut TextureAtlasSprite, visibility: &mut Visibility) {
+        visibility.is_visible = self.explored.contains(&pos);
+        sprite.color = if self.visible.contains(&pos) { WHITE } else { FOG_COLOR };
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MapFile {
+    pub legend: Vec<TileDef>,
+    pub rows: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TileDef {
+    pub glyph: char,
+    pub name: String,
+    pub atlas: TileAtlas,
+    pub sprite_index: usize,
+    #[serde(default)]
+    pub collider: bool,
+    #[serde(default)]
+    pub kind: TileKind,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum TileAtlas {
+    Base,
+    EventObjects,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum TileKind {
+    #[default]
+    Plain,
+    Town,
+    Encounter(Vec<EnemyType>),
+    Lair {
+        enemy_type: EnemyType,
+        sprite_index: usize,
+    },
+    Event {
+        event_type: WorldEventType,
+        lvl: usize,
+    },
+}
+
+impl MapFile {
+    pub fn load(name: &str) -> Self {
+        let path = format!("assets/map/{}.ron", name);
+        let file = File::open(&path).unwrap_or_else(|_| panic!("No map file found: {}", path));
+        from_reader(file).unwrap_or_else(|e| panic!("Unable to load map {}: {}", path, e))
+    }
+
+    /// Saves the map to `assets/map`, one row per line like the hand-made ones.
+    pub fn write(&self, name: &str) -> Result<(), String> {
+        let config = PrettyConfig::new().depth_limit(2).struct_names(true);
+        let content = to_string_pretty(self, config).map_err(|e| e.to_string())?;
+        std::fs::write(format!("assets/map/{}.ron", name), content).map_err(|e| e.to_string())
+    }
+
+    pub fn tile(&self, glyph: char) -> Option<&TileDef> {
+        self.legend.iter().find(|t| t.glyph == glyph)
+    }
+
+    /// Grid positions of the tiles without a collider.
+    pub fn walkable(&self) -> HashSet<GridPos> {
+        let mut walkable = HashSet::new();
+        for (y, line) in self.rows.iter().enumerate() {
+            for (x, glyph) in line.chars().enumerate() {
+                if !self.tile(glyph).is_some_and(|t| t.collider) {
+                    walkable.insert((x as i32, -(y as i32)));
+                }
+            }
+        }
+        walkable
+    }
+
+    /// Every enemy the legend can put on the map.
+    pub fn enemy_types(&self) -> impl Iterator<Item = &EnemyType> {
+        self.legend.iter().flat_map(|t| match &t.kind {
+            TileKind::Encounter(enemy_types) => enemy_types.iter().collect(),
+            TileKind::Lair { enemy_type, .. } => vec![enemy_type],
+            _ => vec![],
+        })
+    }
+}
+
+pub fn grid_position(translation: Vec3) -> (i32, i32) {
+    (translation.x.round() as i32, translation.y.round() as i32)
+}
+
 pub fn hide_map(
     children_query: Query<&Children, With<Map>>,
     child_visibility_query: Query<&mut Visibility, Without<Map>>,
@@ -39,34 +210,65 @@ pub fn hide_map(
 }
 
 pub fn show_map(
-    children_query: Query<&Children, With<Map>>,
-    child_visibility_query: Query<&mut Visibility, Without<Map>>,
+    fog: Res<FogOfWar>,
+    mut tile_query: Query<(&Transform, &mut TextureAtlasSprite, &mut Visibility), TileFilter>,
+) {
+    for (transform, mut sprite, mut visibility) in tile_query.iter_mut() {
+        fog.apply(grid_position(transform.translation), &mut sprite, &mut visibility);
+    }
+}
+
+pub fn update_fog(
+    mut fog: ResMut<FogOfWar>,
+    player_query: Query<&Transform, With<Player>>,
+    mut tile_query: Query<(&Transform, &mut TextureAtlasSprite, &mut Visibility), TileFilter>,
 ) {
-    toggle_visible(children_query, child_visibility_query, true);
+    let center = grid_position(player_query.single().translation);
+
+    if fog.sight_center == Some(center) {
+        return;
+    }
+    fog.reveal(center);
+
+    for (transform, mut sprite, mut visibility) in tile_query.iter_mut() {
+        fog.apply(grid_position(transform.translation), &mut sprite, &mut visibility);
+    }
 }
 
 fn create_map(
     mut commands: Commands,
-    texture_storage: Res<TextureStorage>
+    texture_storage: Res<TextureStorage>,
+    template_storage: Res<TemplateStorage>,
+    rng: Res<GameRng>,
+    mut settings: ResMut<MapSettings>,
+    mut grid: ResMut<MapGrid>,
 ) {
-    let file = File::open("assets/map/map.txt").expect("No map file found");
+    let map = settings.map_file(rng.seed);
+    let errors = template_storage.validate_map(&map);
+    if !errors.is_empty() {
+        errors.iter().for_each(|error| println!("{}", error));
+        panic!("Map {} has {} errors", settings.name, errors.len());
+    }
+    grid.walkable = map.walkable();
     let mut tiles = Vec::new();
 
-    for (y, line) in BufReader::new(file).lines().enumerate() {
-        if let Ok(line) = line {
-            for (x, char) in line.chars().enumerate() {
-                tiles.push(
-                    spawn_tile(
-                        char,
-                        &texture_storage,
-                        &mut commands,
-                        Transform::from_translation(Vec3::new(
-                            x as f32 * TILE_SIZE, -(y as f32) * TILE_SIZE, 100.,
-                        )),
-                        x,
-                    )
-                );
-            }
+    for (y, line) in map.rows.iter().enumerate() {
+        for (x, glyph) in line.chars().enumerate() {
+            let tile_def = map.tile(glyph).unwrap_or_else(|| {
+                panic!("Map {} uses glyph '{}' missing from the legend", settings.name, glyph)
+            });
+
+            tiles.push(
+                spawn_tile(
+                    tile_def,
+                    &texture_storage,
+                    &mut commands,
+                    Transform::from_translation(Vec3::new(
+                        x as f32 * TILE_SIZE, -(y as f32) * TILE_SIZE, 100.,
+                    )),
+                    x,
+                )
+            );
         }
     }
 
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use ron::de::from_reader;
+use serde::Deserialize;
+use crate::prelude::*;
+
+/// Map name that makes a map from the run seed, `generated-<seed>` from a given one.
+pub const GENERATED_MAP: &str = "generated";
+/// Tile the player starts on and respawns at.
+pub const START_TILE: GridPos = (6, -3);
+
+/// Terrain and features within `radius` moves of the start, outside the bands before it.
+#[derive(Clone, Deserialize, Debug)]
+pub struct Band {
+    pub radius: usize,
+    pub collider: char,
+    /// One glyph per feature to place, camps, towns, ruins, altars, encounters or lairs.
+    pub features: Vec<char>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct MapGenerator {
+    pub width: usize,
+    pub height: usize,
+    /// Map whose legend the glyphs come from.
+    pub legend: String,
+    pub start: char,
+    pub path: char,
+    pub bands: Vec<Band>,
+}
+
+impl MapGenerator {
+    pub fn load() -> Self {
+        let file = File::open("assets/MapGen.ron").expect("Failed opening file");
+        from_reader(file).unwrap_or_else(|e| panic!("Unable to load map generator: {}", e))
+    }
+
+    /// Index of the band the tile falls in, the last one past every radius.
+    fn band(&self, pos: GridPos) -> usize {
+        let distance = grid_distance(pos, START_TILE);
+        self.bands
+            .iter()
+            .position(|b| distance <= b.radius)
+            .unwrap_or(self.bands.len() - 1)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = GridPos> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x as i32, -(y as i32))))
+    }
+
+    pub fn generate(&self, seed: u64) -> MapFile {
+        let mut rng = GameRng::map(seed);
+        let legend = MapFile::load(&self.legend).legend;
+
+        let mut glyphs = self
+            .cells()
+            .map(|pos| (pos, self.bands[self.band(pos)].collider))
+            .collect::<HashMap<GridPos, char>>();
+        glyphs.insert(START_TILE, self.start);
+
+        // features keep a tile between each other while there is room
+        let mut features = vec![START_TILE];
+        for (index, band) in self.bands.iter().enumerate() {
+            let mut free = self
+                .cells()
+                .filter(|pos| *pos != START_TILE && self.band(*pos) == index)
+                .collect::<Vec<GridPos>>();
+            free.shuffle(&mut rng);
+
+            for glyph in band.features.iter() {
+                let spaced = free
+                    .iter()
+                    .position(|pos| features.iter().all(|f| grid_distance(*f, *pos) > 1));
+                let index = match spaced.or(if free.is_empty() { None } else { Some(0) }) {
+                    Some(index) => index,
+                    None => break,
+                };
+                let pos = free.remove(index);
+                glyphs.insert(pos, *glyph);
+                features.push(pos);
+            }
+        }
+
+        // every feature walks toward the closest tile already linked to the start
+        let mut connected = HashSet::from([START_TILE]);
+        features.sort_by_key(|pos| grid_distance(*pos, START_TILE));
+        for feature in features.iter().skip(1) {
+            let target = *connected
+                .iter()
+                .min_by_key(|c| (grid_distance(**c, *feature), **c))
+                .unwrap();
+            let mut pos = *feature;
+            let mut trail = vec![pos];
+
+            while !connected.contains(&pos) {
+                let steps = (-1..=1)
+                    .flat_map(|dx| (-1..=1).map(move |dy| (pos.0 + dx, pos.1 + dy)))
+                    .filter(|next| glyphs.contains_key(next))
+                    .filter(|next| grid_distance(*next, target) < grid_distance(pos, target))
+                    .collect::<Vec<GridPos>>();
+                pos = *steps.choose(&mut rng).unwrap();
+                if !features.contains(&pos) {
+                    glyphs.insert(pos, self.path);
+                }
+                trail.push(pos);
+            }
+            connected.extend(trail);
+        }
+
+        let rows = (0..self.height)
+            .map(|y| (0..self.width).map(|x| glyphs[&(x as i32, -(y as i32))]).collect::<String>())
+            .collect();
+
+        MapFile { legend, rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 8] = [0, 1, 5, 9, 42, 77, 1234, u64::MAX];
+
+    #[test]
+    fn every_walkable_tile_has_a_path_from_start() {
+        let generator = MapGenerator::load();
+        for seed in SEEDS {
+            let walkable = generator.generate(seed).walkable();
+            assert!(walkable.contains(&START_TILE), "seed {}: start is not walkable", seed);
+
+            for pos in walkable.iter().filter(|pos| **pos != START_TILE) {
+                assert!(
+                    find_path(&walkable, START_TILE, *pos).is_some(),
+                    "seed {}: no path to {:?}", seed, pos
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_rows() {
+        let generator = MapGenerator::load();
+        for seed in SEEDS {
+            assert_eq!(generator.generate(seed).rows, generator.generate(seed).rows, "seed {}", seed);
+        }
+        assert_ne!(generator.generate(1).rows, generator.generate(2).rows);
+    }
+
+    #[test]
+    fn generated_maps_validate() {
+        let generator = MapGenerator::load();
+        let storage = Templates::load().storage();
+        for seed in SEEDS {
+            let errors = storage.validate_map(&generator.generate(seed));
+            assert!(errors.is_empty(), "seed {}: {:?}", seed, errors);
+        }
+    }
+}
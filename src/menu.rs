@@ -1,4 +1,5 @@
 use bevy::app::AppExit;
+use serde::{Deserialize, Serialize};
 use crate::KeyCode::Escape;
 use crate::prelude::*;
 
@@ -19,11 +20,26 @@ impl Plugin for MenuPlugin {
             .add_system_set(
                 SystemSet::on_update(Menu)
                     .with_system(start_button)
+                    .with_system(resume_button)
+                    .with_system(continue_button)
+                    .with_system(save_button)
                     .with_system(exit_button)
             )
             .add_system_set(
                 SystemSet::on_exit(Menu)
                     .with_system(despawn_menu)
+            )
+            .add_system_set(
+                SystemSet::on_enter(ClassSelect)
+                    .with_system(spawn_class_select)
+            )
+            .add_system_set(
+                SystemSet::on_update(ClassSelect)
+                    .with_system(class_button)
+            )
+            .add_system_set(
+                SystemSet::on_exit(ClassSelect)
+                    .with_system(despawn_menu)
             );
     }
 
@@ -44,6 +60,9 @@ pub fn esc(
 #[derive(Component)]
 pub struct ButtonActive(pub bool);
 
+type MenuButtonQuery<'w, 's, 'a, T> =
+    Query<'w, 's, (&'a Children, &'a mut ButtonActive, &'a Interaction), (With<T>, Changed<Interaction>)>;
+
 pub struct UiAssets {
     pub font: Handle<Font>,
     pub button: Handle<Image>,
@@ -56,11 +75,29 @@ pub struct UiCameraMarker;
 #[derive(Component)]
 pub struct StartButton;
 
+/// Takes the place of `StartButton` once a class is picked, so a run can't be started over on top of itself.
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct ContinueButton;
+
+#[derive(Component)]
+pub struct SaveButton;
+
 #[derive(Component)]
 pub struct ExitButton;
 
-enum ButtonAction {
+/// Index in `HeroClasses`.
+#[derive(Component)]
+pub struct ClassButton(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ButtonAction {
     Start,
+    Resume,
+    Continue,
+    Save,
     Exit
 }
 
@@ -79,7 +116,12 @@ fn despawn_menu(
 }
 
 
-pub fn spawn_menu(mut commands: Commands, texture_storage: Res<TextureStorage>) {
+pub fn spawn_menu(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    rng: Res<GameRng>,
+    player_query: Query<&Player>,
+) {
     commands.spawn_bundle(
         UiCameraBundle {
             transform: Transform::from_xyz(0., 0., 1000.),
@@ -87,13 +129,36 @@ pub fn spawn_menu(mut commands: Commands, texture_storage: Res<TextureStorage>)
         })
         .insert(UiCameraMarker);
 
+    let start_position = Rect {
+        left: Val::Percent(37.5),
+        ..default()
+    };
+    if player_query.single().class.is_empty() {
+        spawn_menu_button(&texture_storage, &mut commands, StartButton, String::from("Start game"), start_position);
+    } else {
+        spawn_menu_button(&texture_storage, &mut commands, ResumeButton, String::from("Resume game"), start_position);
+    }
+    if SaveFile::exists() {
+        spawn_menu_button(
+            &texture_storage,
+            &mut commands,
+            ContinueButton,
+            String::from("Continue"),
+            Rect {
+                left: Val::Percent(12.5),
+                top: Val::Percent(11.),
+                ..default()
+            }
+        );
+    }
     spawn_menu_button(
         &texture_storage,
         &mut commands,
-        StartButton,
-        String::from("Start game"),
+        SaveButton,
+        String::from("Save game"),
         Rect {
-            left: Val::Percent(25.),
+            right: Val::Percent(12.5),
+            top: Val::Percent(22.),
             ..default()
         }
     );
@@ -103,15 +168,37 @@ pub fn spawn_menu(mut commands: Commands, texture_storage: Res<TextureStorage>)
         ExitButton,
         String::from("Exit game"),
         Rect {
-            right: Val::Percent(25.),
-            top: Val::Percent(6.),
+            right: Val::Percent(37.5),
+            top: Val::Percent(33.),
             ..default()
         }
     );
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(20.),
+                    bottom: Val::Px(20.),
+                    ..default()
+                },
+                ..default()
+            },
+            text: Text::with_section(
+                format!("Seed: {}", rng.seed),
+                TextStyle {
+                    font: texture_storage.font.clone(),
+                    font_size: 18.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                default(),
+            ),
+            ..default()
+        })
+        .insert(UiCameraMarker);
     spawn_combat_battleground(
         &mut commands,
-        &texture_storage,
-        &EnemyType::Demon,
+        &texture_storage.menu_background,
         Transform::from_xyz(10., -10., 800.)
     );
 }
@@ -119,46 +206,117 @@ pub fn spawn_menu(mut commands: Commands, texture_storage: Res<TextureStorage>)
 fn start_button(
     state: ResMut<State<GameState>>,
     exit: EventWriter<AppExit>,
-    interaction_query:
-    Query<(&Children, &mut ButtonActive, &Interaction), (With<StartButton>, Changed<Interaction>)>,
+    save_event: EventWriter<SaveEvent>,
+    interaction_query: MenuButtonQuery<StartButton>,
     image_query: Query<&mut UiImage>,
     ui_assets: Res<UiAssets>,
+    replay: ResMut<Replay>,
 ) {
     button_listener(
         state,
         exit,
+        save_event,
         interaction_query,
         image_query,
         ui_assets,
+        replay,
         ButtonAction::Start
     );
 }
 
+fn resume_button(
+    state: ResMut<State<GameState>>,
+    exit: EventWriter<AppExit>,
+    save_event: EventWriter<SaveEvent>,
+    interaction_query: MenuButtonQuery<ResumeButton>,
+    image_query: Query<&mut UiImage>,
+    ui_assets: Res<UiAssets>,
+    replay: ResMut<Replay>,
+) {
+    button_listener(
+        state,
+        exit,
+        save_event,
+        interaction_query,
+        image_query,
+        ui_assets,
+        replay,
+        ButtonAction::Resume
+    );
+}
+
+fn continue_button(
+    state: ResMut<State<GameState>>,
+    exit: EventWriter<AppExit>,
+    save_event: EventWriter<SaveEvent>,
+    interaction_query: MenuButtonQuery<ContinueButton>,
+    image_query: Query<&mut UiImage>,
+    ui_assets: Res<UiAssets>,
+    replay: ResMut<Replay>,
+) {
+    button_listener(
+        state,
+        exit,
+        save_event,
+        interaction_query,
+        image_query,
+        ui_assets,
+        replay,
+        ButtonAction::Continue
+    );
+}
+
+fn save_button(
+    state: ResMut<State<GameState>>,
+    exit: EventWriter<AppExit>,
+    save_event: EventWriter<SaveEvent>,
+    interaction_query: MenuButtonQuery<SaveButton>,
+    image_query: Query<&mut UiImage>,
+    ui_assets: Res<UiAssets>,
+    replay: ResMut<Replay>,
+) {
+    button_listener(
+        state,
+        exit,
+        save_event,
+        interaction_query,
+        image_query,
+        ui_assets,
+        replay,
+        ButtonAction::Save
+    );
+}
+
 fn exit_button(
     state: ResMut<State<GameState>>,
     exit: EventWriter<AppExit>,
-    interaction_query:
-    Query<(&Children, &mut ButtonActive, &Interaction), (With<ExitButton>, Changed<Interaction>)>,
+    save_event: EventWriter<SaveEvent>,
+    interaction_query: MenuButtonQuery<ExitButton>,
     image_query: Query<&mut UiImage>,
     ui_assets: Res<UiAssets>,
+    replay: ResMut<Replay>,
 ) {
     button_listener(
         state,
         exit,
+        save_event,
         interaction_query,
         image_query,
         ui_assets,
+        replay,
         ButtonAction::Exit
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn button_listener<T: Component>(
     mut state: ResMut<State<GameState>>,
     mut exit: EventWriter<AppExit>,
-    mut interaction_query:
-    Query<(&Children, &mut ButtonActive, &Interaction), (With<T>, Changed<Interaction>)>,
+    mut save_event: EventWriter<SaveEvent>,
+    mut interaction_query: MenuButtonQuery<T>,
     mut image_query: Query<&mut UiImage>,
     ui_assets: Res<UiAssets>,
+    mut replay: ResMut<Replay>,
     button_action: ButtonAction
 ) {
 
@@ -173,10 +331,21 @@ fn button_listener<T: Component>(
                     image.0 = ui_assets.button_pressed.clone();
                     active.0 = false;
                 }
+                replay.record(ReplayAction::Button(button_action));
                 match button_action {
                     ButtonAction::Start => {
+                        state.set(ClassSelect).unwrap();
+                    },
+                    ButtonAction::Resume => {
+                        state.set(World).unwrap();
+                    },
+                    ButtonAction::Continue => {
+                        save_event.send(SaveEvent::Load);
                         state.set(World).unwrap();
                     },
+                    ButtonAction::Save => {
+                        save_event.send(SaveEvent::Save);
+                    },
                     ButtonAction::Exit => {
                         exit.send(AppExit);
                     }
@@ -189,3 +358,86 @@ fn button_listener<T: Component>(
         }
     }
 }
+
+pub fn spawn_class_select(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    classes: Res<HeroClasses>,
+) {
+    commands.spawn_bundle(
+        UiCameraBundle {
+            transform: Transform::from_xyz(0., 0., 1000.),
+            ..default()
+        })
+        .insert(UiCameraMarker);
+
+    // buttons share a row, shifting each one to the middle stacks them in a column
+    let count = classes.0.len() as f32;
+    for (index, class) in classes.0.iter().enumerate() {
+        let center = (index as f32 + 0.5) * 100. / count;
+        spawn_menu_button(
+            &texture_storage,
+            &mut commands,
+            ClassButton(index),
+            class.name.clone(),
+            Rect {
+                left: Val::Percent(50. - center),
+                top: Val::Percent(11. * index as f32),
+                ..default()
+            }
+        );
+    }
+
+    let descriptions = classes.0
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.description))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(20.),
+                    bottom: Val::Px(20.),
+                    ..default()
+                },
+                ..default()
+            },
+            text: Text::with_section(
+                descriptions,
+                TextStyle {
+                    font: texture_storage.font.clone(),
+                    font_size: 18.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+                default(),
+            ),
+            ..default()
+        })
+        .insert(UiCameraMarker);
+    spawn_combat_battleground(
+        &mut commands,
+        &texture_storage.menu_background,
+        Transform::from_xyz(10., -10., 800.)
+    );
+}
+
+fn class_button(
+    mut state: ResMut<State<GameState>>,
+    interaction_query: Query<(&ClassButton, &Interaction), Changed<Interaction>>,
+    mut replay: ResMut<Replay>,
+    mut class_event: EventWriter<ClassEvent>,
+) {
+    for (button, interaction) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        replay.record(ReplayAction::Class(button.0));
+        class_event.send(ClassEvent(button.0));
+        state.set(World).expect("Failed to change states");
+        return;
+    }
+}
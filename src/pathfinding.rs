@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+pub type GridPos = (i32, i32);
+
+const NEIGHBOURS: [GridPos; 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1),
+];
+
+/// Every move, diagonal included, costs one move point.
+pub fn grid_distance(a: GridPos, b: GridPos) -> usize {
+    std::cmp::max((a.0 - b.0).unsigned_abs(), (a.1 - b.1).unsigned_abs()) as usize
+}
+
+fn neighbours(pos: GridPos) -> impl Iterator<Item = GridPos> {
+    NEIGHBOURS.iter().map(move |(dx, dy)| (pos.0 + dx, pos.1 + dy))
+}
+
+/// A* over the walkable tiles, returns the steps after `start` up to and including `goal`.
+pub fn find_path(walkable: &HashSet<GridPos>, start: GridPos, goal: GridPos) -> Option<Vec<GridPos>> {
+    if start == goal || !walkable.contains(&goal) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<GridPos, GridPos> = HashMap::new();
+    let mut cost: HashMap<GridPos, usize> = HashMap::new();
+
+    cost.insert(start, 0);
+    open.push(Reverse((grid_distance(start, goal), 0, start)));
+
+    while let Some(Reverse((_, steps, current))) = open.pop() {
+        if current == goal {
+            let mut path = vec![current];
+            let mut node = current;
+            while let Some(prev) = came_from.get(&node) {
+                if *prev == start {
+                    break;
+                }
+                path.push(*prev);
+                node = *prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        if cost.get(&current).is_some_and(|c| *c < steps) {
+            continue;
+        }
+
+        for next in neighbours(current).filter(|p| walkable.contains(p)) {
+            let next_steps = steps + 1;
+            if cost.get(&next).is_none_or(|c| next_steps < *c) {
+                cost.insert(next, next_steps);
+                came_from.insert(next, current);
+                open.push(Reverse((next_steps + grid_distance(next, goal), next_steps, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Tiles reachable from `start` in at most `steps` moves, `start` excluded.
+pub fn reachable_tiles(walkable: &HashSet<GridPos>, start: GridPos, steps: usize) -> HashSet<GridPos> {
+    let mut reached = HashSet::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut seen = HashSet::from([start]);
+
+    while let Some((current, distance)) = queue.pop_front() {
+        if distance == steps {
+            continue;
+        }
+
+        for next in neighbours(current).filter(|p| walkable.contains(p)) {
+            if seen.insert(next) {
+                reached.insert(next);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `#` is a collider, any other glyph is walkable. Rows go down in y.
+    fn grid(rows: &[&str]) -> HashSet<GridPos> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.chars().enumerate().map(move |(x, glyph)| ((x as i32, y as i32), glyph)))
+            .filter(|(_, glyph)| *glyph != '#')
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    #[test]
+    fn path_goes_around_colliders() {
+        let walkable = grid(&[
+            ".....",
+            "###.#",
+            ".....",
+        ]);
+
+        let path = find_path(&walkable, (0, 0), (0, 2)).unwrap();
+        assert_eq!(path.len(), 6);
+        assert!(path.contains(&(3, 1)));
+        assert!(path.iter().all(|pos| walkable.contains(pos)));
+        assert!(path.windows(2).all(|step| grid_distance(step[0], step[1]) == 1));
+        assert_eq!(path.last(), Some(&(0, 2)));
+    }
+
+    #[test]
+    fn diagonal_costs_one_step() {
+        let walkable = grid(&[".....", ".....", ".....", ".....", "....."]);
+
+        let path = find_path(&walkable, (0, 0), (4, 4)).unwrap();
+        assert_eq!(path, vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(grid_distance((0, 0), (4, 4)), 4);
+    }
+
+    #[test]
+    fn unreachable_target_has_no_path() {
+        let walkable = grid(&[
+            "..#..",
+            "..#..",
+            "..#..",
+        ]);
+
+        assert_eq!(find_path(&walkable, (0, 0), (4, 0)), None);
+        assert_eq!(find_path(&walkable, (0, 0), (2, 1)), None);
+        assert_eq!(find_path(&walkable, (0, 0), (0, 0)), None);
+    }
+
+    #[test]
+    fn reachable_tiles_respect_move_budget() {
+        let walkable = grid(&[".......", ".......", ".......", ".......", ".......", ".......", "......."]);
+
+        assert!(reachable_tiles(&walkable, (3, 3), 0).is_empty());
+        assert_eq!(reachable_tiles(&walkable, (3, 3), 1).len(), 8);
+        assert_eq!(reachable_tiles(&walkable, (3, 3), 2).len(), 24);
+        assert!(!reachable_tiles(&walkable, (3, 3), 2).contains(&(3, 3)));
+        assert!(!reachable_tiles(&walkable, (3, 3), 2).contains(&(0, 3)));
+
+        let walled = grid(&[
+            "...",
+            "##.",
+            "...",
+        ]);
+        // two rows down in a straight line, but the wall makes it a four-move detour
+        assert!(!reachable_tiles(&walled, (0, 0), 3).contains(&(0, 2)));
+        assert!(reachable_tiles(&walled, (0, 0), 3).contains(&(1, 2)));
+        assert!(reachable_tiles(&walled, (0, 0), 4).contains(&(0, 2)));
+    }
+}
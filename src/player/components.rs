@@ -1,12 +1,33 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
 use crate::prelude::*;
 
-#[derive(Component, Inspectable, Default)]
+#[derive(Component, Inspectable, Default, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub is_selected: bool,
     pub combat_deck: Vec<CardView>,
     pub deck: Vec<CardView>,
     pub items_bag: Vec<ItemView>,
-    pub item_build: ItemBuild
+    #[inspectable(ignore)]
+    pub item_build: ItemBuild,
+    #[serde(default)]
+    pub gold: usize,
+    /// Max health taken by altars, see `derive_stats`.
+    #[serde(default)]
+    pub altar_penalty: isize,
+    #[inspectable(ignore)]
+    #[serde(default)]
+    pub rewards: Vec<StatModifier>,
+    #[serde(default)]
+    pub xp: usize,
+    #[inspectable(ignore)]
+    #[serde(default)]
+    pub talents: Vec<TalentEffect>,
+    /// Name of the hero class, see `HeroClasses`.
+    #[serde(default)]
+    pub class: String,
+    #[serde(default)]
+    pub base_stats: CombatStats,
 }
 
 impl Player {
@@ -23,11 +44,19 @@ impl Player {
     }
 
     pub fn can_add(&self, lvl: usize) -> bool {
-        match lvl {
-            1 => self.spell_count(lvl) < 3,
-            2 => self.spell_count(lvl) < 2,
-            _ => self.spell_count(lvl) == 0
-        }
+        let slots = match lvl {
+            1 => 3,
+            2 => 2,
+            _ => 1,
+        };
+        self.spell_count(lvl) < slots + self.talent_deck_slots(lvl)
+    }
+
+    fn talent_deck_slots(&self, lvl: usize) -> usize {
+        self.talents
+            .iter()
+            .filter(|t| **t == TalentEffect::DeckSlot(lvl))
+            .count()
     }
 
     fn spell_count(&self, lvl: usize) -> usize {
@@ -45,19 +74,62 @@ impl Player {
         self.deck.push(card_view);
     }
 
+    /// Drops a card from the deck, along with a combat deck copy the deck no longer covers.
+    pub fn sell_card(&mut self, id: usize) -> bool {
+        let index = match self.deck.iter().position(|c| c.id == id) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.deck.remove(index);
+
+        let in_deck = self.deck.iter().filter(|c| c.id == id).count();
+        let in_combat_deck = self.combat_deck.iter().filter(|c| c.id == id).count();
+        if in_combat_deck > in_deck {
+            self.remove(id);
+        }
+        true
+    }
+
     pub fn add_in_bag(&mut self, id: usize, level: usize) {
         let item_view = ItemView {
             id,
-            level
+            level,
+            bonus: 0,
         };
         self.items_bag.push(item_view);
     }
 }
 
-#[derive(Component, Inspectable, Default)]
-pub struct ItemBuild {
-    pub defense: Option<(usize, usize)>,//(id, value)
-    pub attack: Option<(usize, usize)>,
-    pub mana: Option<(usize, usize)>,
-    pub health: Option<(usize, usize)>,
+#[derive(Component)]
+pub struct PlayerPath {
+    pub steps: VecDeque<GridPos>,
+    pub timer: Timer,
+}
+
+impl Default for PlayerPath {
+    fn default() -> Self {
+        Self {
+            steps: VecDeque::new(),
+            timer: Timer::from_seconds(0.2, true),
+        }
+    }
+}
+
+impl PlayerPath {
+    pub fn is_walking(&self) -> bool {
+        !self.steps.is_empty()
+    }
+}
+
+#[derive(Component)]
+pub struct ReachableMarker;
+
+#[derive(Component)]
+pub struct PathMarker;
+
+/// What the reachable tiles and the route preview were last drawn for.
+#[derive(Default)]
+pub struct PathPreview {
+    pub reachable: Option<(GridPos, isize)>,
+    pub route: Option<(GridPos, GridPos, isize)>,
 }
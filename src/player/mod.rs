@@ -1,8 +1,10 @@
 mod components;
 mod systems;
+mod stats;
 
 pub use crate::player::systems::*;
 pub use crate::player::components::*;
+pub use crate::player::stats::*;
 use crate::prelude::*;
 
 
@@ -15,14 +17,19 @@ impl Plugin for PlayerPlugin {
                 SystemSet::on_enter(World)
                     .with_system(show_player)
             )
+            .init_resource::<PathPreview>()
             .add_system_set(
                 SystemSet::on_update(World)
-                    .with_system(player_encounter_checking.after(player_movement))
+                    .with_system(player_encounter_checking.after(player_walk))
+                    .with_system(player_walk.after(player_movement))
                     .with_system(player_movement.after(cursor_position))
+                    .with_system(reachable_highlight.after(player_walk))
+                    .with_system(path_preview.after(player_walk))
             )
             .add_system_set(
                 SystemSet::on_exit(World)
                     .with_system(hide_player)
+                    .with_system(clear_path_markers)
             )
             .add_startup_system(spawn_player);
     }
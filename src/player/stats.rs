@@ -0,0 +1,205 @@
+use crate::prelude::*;
+
+/// Altars can't take max health below this.
+pub const ALTAR_MIN_HEALTH: isize = 5;
+
+impl Player {
+    /// Rebuilds the combat stats from the class base stats, worn gear and set bonuses, altar penalties,
+    /// permanent rewards and talents. Only current health carries over, so running it again
+    /// changes nothing.
+    pub fn derive_stats(&self, stats: &mut CombatStats, sets: &[ItemSet]) {
+        let base = &self.base_stats;
+        let total = |stat: Stat| self.item_build.total(stat, sets) + self.reward_total(stat);
+
+        let max_health = base.max_health + total(Stat::MaxHealth);
+        let floor = std::cmp::min(max_health, ALTAR_MIN_HEALTH);
+
+        stats.attack = base.attack + total(Stat::Attack);
+        stats.defense = base.defense + total(Stat::Defense);
+        stats.mana = base.mana + total(Stat::Mana);
+        stats.max_health = std::cmp::max(max_health - self.altar_penalty, floor);
+        stats.health = std::cmp::min(stats.health, stats.max_health);
+    }
+
+    fn reward_total(&self, stat: Stat) -> isize {
+        let talents = self.talents.iter().filter_map(|t| match t {
+            TalentEffect::Stat(modifier) => Some(modifier),
+            _ => None,
+        });
+
+        self.rewards
+            .iter()
+            .chain(talents)
+            .filter(|m| m.stat == stat)
+            .map(|m| m.value)
+            .sum()
+    }
+
+    pub fn add_reward(&mut self, reward: StatModifier) {
+        match self.rewards.iter_mut().find(|m| m.stat == reward.stat) {
+            Some(modifier) => modifier.value += reward.value,
+            None => self.rewards.push(reward),
+        }
+    }
+
+    /// Wears a bag item, the one it replaces goes back to the bag.
+    pub fn equip_from_bag(&mut self, bag_index: usize, storage: &TemplateStorage) -> bool {
+        let view = match self.items_bag.get(bag_index) {
+            Some(view) => *view,
+            None => return false,
+        };
+        let template = storage.items
+            .iter()
+            .find(|t| t.id == view.id)
+            .expect("Unknown item template");
+        let slot = match template.item_slot() {
+            Some(slot) => slot,
+            None => return false,
+        };
+
+        let mut item = template.equipped();
+        if let Some(modifier) = item.modifiers.first_mut() {
+            modifier.value += view.bonus;
+        }
+
+        let replaced = match self.item_build.equip(slot, item) {
+            Some((_, replaced)) => replaced,
+            None => return false,
+        };
+        self.items_bag.remove(bag_index);
+        if let Some(replaced) = replaced {
+            self.add_in_bag_upgraded(storage, &replaced);
+        }
+        true
+    }
+
+    /// Takes the item off and puts it back in the bag, forge upgrades included.
+    pub fn unequip(&mut self, index: usize, storage: &TemplateStorage) -> bool {
+        let item = match self.item_build.unequip(index) {
+            Some(item) => item,
+            None => return false,
+        };
+        self.add_in_bag_upgraded(storage, &item);
+        true
+    }
+
+    pub fn add_in_bag_upgraded(&mut self, storage: &TemplateStorage, item: &EquippedItem) {
+        let template = storage.items
+            .iter()
+            .find(|t| t.id == item.id)
+            .expect("Unknown item template");
+
+        let base = template.item_modifiers().first().map_or(0, |m| m.value);
+        let upgraded = item.modifiers.first().map_or(0, |m| m.value);
+
+        self.items_bag.push(ItemView {
+            id: item.id,
+            level: template.level,
+            bonus: upgraded - base,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sets() -> Vec<ItemSet> {
+        vec![ItemSet {
+            name: "Grove".to_string(),
+            pieces: 2,
+            modifiers: vec![StatModifier { stat: Stat::MaxHealth, value: 2 }],
+        }]
+    }
+
+    fn item(id: usize, stat: Stat, value: isize) -> EquippedItem {
+        EquippedItem {
+            id,
+            set: Some("Grove".to_string()),
+            modifiers: vec![StatModifier { stat, value }],
+        }
+    }
+
+    fn derived(player: &Player, stats: &mut CombatStats) -> (isize, isize, isize, isize) {
+        player.derive_stats(stats, &sets());
+        (stats.attack, stats.defense, stats.mana, stats.max_health)
+    }
+
+    #[test]
+    fn derive_is_idempotent() {
+        let mut player = Player::default();
+        let mut stats = CombatStats::new_player();
+        player.item_build.equip(SlotKind::Amulet, item(1, Stat::MaxHealth, 3));
+
+        let first = derived(&player, &mut stats);
+        // opening and closing the bag over and over
+        for _ in 0..5 {
+            assert_eq!(derived(&player, &mut stats), first);
+        }
+        assert_eq!(first, (0, 0, 0, 13));
+    }
+
+    #[test]
+    fn equip_and_unequip_restore_stats() {
+        let mut player = Player::default();
+        let mut stats = CombatStats::new_player();
+        let start = derived(&player, &mut stats);
+
+        let weapon = player.item_build
+            .equip(SlotKind::Weapon, item(1, Stat::Attack, 2))
+            .map(|(index, _)| index)
+            .unwrap();
+        player.item_build.equip(SlotKind::Body, item(2, Stat::Defense, 1));
+        assert_eq!(derived(&player, &mut stats), (2, 1, 0, 12));
+
+        assert!(player.item_build.unequip(weapon).is_some());
+        assert!(player.item_build.unequip(weapon).is_none());
+        assert_eq!(derived(&player, &mut stats), (0, 1, 0, 10));
+
+        player.item_build = ItemBuild::default();
+        assert_eq!(derived(&player, &mut stats), start);
+    }
+
+    #[test]
+    fn unequip_clamps_health() {
+        let mut player = Player::default();
+        let mut stats = CombatStats::new_player();
+        let amulet = player.item_build
+            .equip(SlotKind::Amulet, item(1, Stat::MaxHealth, 4))
+            .map(|(index, _)| index)
+            .unwrap();
+        derived(&player, &mut stats);
+        stats.health = stats.max_health;
+        assert_eq!(stats.health, 14);
+
+        player.item_build.unequip(amulet);
+        derived(&player, &mut stats);
+        assert_eq!(stats.health, 10);
+    }
+
+    #[test]
+    fn replaced_item_is_returned() {
+        let mut build = ItemBuild::default();
+        build.equip(SlotKind::Weapon, item(1, Stat::Attack, 1));
+
+        assert_eq!(build.equip(SlotKind::Weapon, item(2, Stat::Attack, 0)), None);
+        let (_, replaced) = build.equip(SlotKind::Weapon, item(3, Stat::Attack, 2)).unwrap();
+        assert_eq!(replaced.map(|i| i.id), Some(1));
+    }
+
+    #[test]
+    fn altar_penalty_and_rewards_survive_recompute() {
+        let mut player = Player::default();
+        let mut stats = CombatStats::new_player();
+
+        player.altar_penalty = 2;
+        player.add_reward(StatModifier { stat: Stat::MaxHealth, value: 1 });
+        player.add_reward(StatModifier { stat: Stat::MaxHealth, value: 1 });
+        assert_eq!(player.rewards.len(), 1);
+        assert_eq!(derived(&player, &mut stats).3, 10);
+        assert_eq!(derived(&player, &mut stats).3, 10);
+
+        player.altar_penalty = 20;
+        assert_eq!(derived(&player, &mut stats).3, ALTAR_MIN_HEALTH);
+    }
+}
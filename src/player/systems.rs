@@ -3,40 +3,168 @@ use crate::prelude::*;
 use crate::player::components::*;
 
 pub fn player_movement(
-    mut player: Query<&mut Transform, With<Player>>,
-    wall_query: Query<&Transform, (With<TileCollider>, Without<Player>)>,
-    cursor_state: ResMut<CursorState>,
+    mut player_query: Query<(&Transform, &mut PlayerPath), With<Player>>,
+    grid: Res<MapGrid>,
+    fog: Res<FogOfWar>,
+    cursor_state: Res<CursorState>,
     buttons: Res<Input<MouseButton>>,
+    move_points_query: Query<&MoveDice>,
+) {
+    let (transform, mut path) = player_query.single_mut();
+    let move_points = move_points_query.single();
+
+    if buttons.just_pressed(MouseButton::Right) && move_points.value > 0 && !path.is_walking() {
+        let start = grid_position(transform.translation);
+        let goal = grid_position(cursor_state.last_right_click.extend(0.));
+
+        if let Some(steps) = find_path(&fog.known_walkable(&grid), start, goal) {
+            path.steps = steps.into_iter().take(move_points.value as usize).collect();
+            path.timer.reset();
+        }
+    }
+}
+
+pub fn player_walk(
+    mut player_query: Query<(&mut Transform, &mut TextureAtlasSprite, &mut PlayerPath), With<Player>>,
+    encounter_query: Query<(&Transform, &EncounterType), SpawnerFilter>,
     mut move_points_query: Query<&mut MoveDice>,
+    time: Res<GameClock>,
 ) {
-    let mut transform = player.single_mut();
+    let (mut transform, mut sprite, mut path) = player_query.single_mut();
     let mut move_points = move_points_query.single_mut();
 
-    if buttons.just_pressed(MouseButton::Right) && move_points.value != 0 {
-        let x_target = cursor_state.last_right_click.x;
-        let y_target = cursor_state.last_right_click.y;
-
-        let x_ = (transform.translation.x - x_target).abs();
-        let y_ = (transform.translation.y - y_target).abs();
-
-        if x_ <= 1. && y_ <= 1. {
-            let target = Vec3::new(x_target, y_target, 100.);
-
-            if !wall_query
-                .iter()
-                .any(|&transform|
-                    collide_check(target, transform.translation))
-            {
-                move_points.value = std::cmp::max(
-                    move_points.value - 1,
-                    0,
-                );
-
-                transform.translation.x = cursor_state.last_right_click.x;
-                transform.translation.y = cursor_state.last_right_click.y;
-            }
+    if !path.is_walking() {
+        return;
+    }
+
+    path.timer.tick(time.delta());
+    if !path.timer.just_finished() {
+        return;
+    }
+
+    if move_points.value <= 0 {
+        path.steps.clear();
+        return;
+    }
+
+    if let Some(step) = path.steps.pop_front() {
+        sprite.flip_x = (step.0 as f32) < transform.translation.x;
+        transform.translation.x = step.0 as f32;
+        transform.translation.y = step.1 as f32;
+        move_points.value -= 1;
+
+        if encounter_query
+            .iter()
+            .any(|(transform, enc_type)| !enc_type.1 && grid_position(transform.translation) == step)
+        {
+            path.steps.clear();
+        }
+    }
+}
+
+pub fn reachable_highlight(
+    mut commands: Commands,
+    mut preview: ResMut<PathPreview>,
+    grid: Res<MapGrid>,
+    fog: Res<FogOfWar>,
+    player_query: Query<(&Transform, &PlayerPath), With<Player>>,
+    move_dice_query: Query<&MoveDice>,
+    marker_query: Query<Entity, With<ReachableMarker>>,
+) {
+    let (transform, path) = player_query.single();
+    let value = if path.is_walking() { 0 } else { move_dice_query.single().value };
+    let key = (grid_position(transform.translation), value);
+
+    if preview.reachable == Some(key) {
+        return;
+    }
+    preview.reachable = Some(key);
+
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if value <= 0 {
+        return;
+    }
+
+    for pos in reachable_tiles(&fog.known_walkable(&grid), key.0, value as usize) {
+        spawn_tile_marker(&mut commands, pos, REACHABLE_COLOR, 150., ReachableMarker);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn path_preview(
+    mut commands: Commands,
+    mut preview: ResMut<PathPreview>,
+    grid: Res<MapGrid>,
+    fog: Res<FogOfWar>,
+    cursor_state: Res<CursorState>,
+    texture_storage: Res<TextureStorage>,
+    player_query: Query<(&Transform, &PlayerPath), With<Player>>,
+    move_dice_query: Query<&MoveDice>,
+    marker_query: Query<Entity, With<PathMarker>>,
+) {
+    let (transform, path) = player_query.single();
+    let value = if path.is_walking() { 0 } else { move_dice_query.single().value };
+    let start = grid_position(transform.translation);
+    let goal = grid_position(cursor_state.world_position.extend(0.));
+    let key = (start, goal, value);
+
+    if preview.route == Some(key) {
+        return;
+    }
+    preview.route = Some(key);
+
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if value <= 0 {
+        return;
+    }
+
+    if let Some(steps) = find_path(&fog.known_walkable(&grid), start, goal) {
+        for (i, pos) in steps.iter().enumerate() {
+            let color = if (i as isize) < value { PATH_COLOR } else { PATH_OUT_OF_REACH_COLOR };
+            spawn_tile_marker(&mut commands, *pos, color, 160., PathMarker);
         }
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    steps.len().to_string(),
+                    TextStyle {
+                        font: texture_storage.font.clone(),
+                        font_size: 40.0,
+                        color: WHITE,
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                ),
+                transform: Transform {
+                    translation: Vec3::new(goal.0 as f32, goal.1 as f32, 170.),
+                    scale: Vec3::new(0.01, 0.01, 0.),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Name::new("Path cost"))
+            .insert(PathMarker);
+    }
+}
+
+pub fn clear_path_markers(
+    mut commands: Commands,
+    mut preview: ResMut<PathPreview>,
+    marker_query: Query<Entity, PathMarkerFilter>,
+) {
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
     }
+    *preview = PathPreview::default();
 }
 
 pub fn player_encounter_checking(
@@ -52,7 +180,7 @@ pub fn player_encounter_checking(
     for (transform, enc_type) in encounter_query.iter() {
         if collide_check(transform.translation, player_translation) && !enc_type.1 {
             move_dice.value = 0_isize;
-            encounter_event.send(EncounterEvent(enc_type.0));
+            encounter_event.send(EncounterEvent(enc_type.0.clone()));
             state.set(Combat).expect("Failed to change states");
         }
     }
@@ -68,7 +196,7 @@ pub fn collide_check(target_player_pos: Vec3, wall_translation: Vec3) -> bool {
     collision.is_some()
 }
 
-pub fn spawn_player(mut commands: Commands, frame_sheet: Res<FramesSheet>) {
+pub fn spawn_player(mut commands: Commands) {
     let sprite = TextureAtlasSprite {
         index: 0,
         color: Color::rgb(1., 1., 1.),
@@ -76,52 +204,35 @@ pub fn spawn_player(mut commands: Commands, frame_sheet: Res<FramesSheet>) {
         ..default()
     };
 
-    let animation = FrameAnimation {
-        timer: Timer::from_seconds(0.2, true),
-        frames: frame_sheet.hero_idle.to_vec(),
-        current_frame: 0,
-    };
-
-    let item_build = ItemBuild {
-        defense: None,
-        attack: None,
-        mana: None,
-        health: None,
-    };
-
-    let mut player = Player {
+    let player = Player {
         is_selected: false,
         combat_deck: vec![],
         items_bag: vec![],
-        item_build,
+        item_build: ItemBuild::default(),
         deck: vec![],
+        gold: 0,
+        altar_penalty: 0,
+        rewards: vec![],
+        xp: 0,
+        talents: vec![],
+        class: String::new(),
+        base_stats: CombatStats::new_player(),
     };
 
-    player.add_in_deck(1, 1);
-    player.add_in_deck(5, 1);
-
+    // the sheet and animations of the class come with `apply_class_sheets`
     let _ = commands.spawn_bundle(SpriteSheetBundle {
         sprite,
-        texture_atlas: frame_sheet.hero_idle_atlas_handle.clone(),
         transform: Transform {
-            translation: Vec3::new(6., -3., 500.),
+            translation: Vec3::new(START_TILE.0 as f32, START_TILE.1 as f32, 500.),
             ..default()
         },
         ..Default::default()
     })
         .insert(Name::new("Player"))
         .insert(player)
-        .insert(CombatStats {
-            health: 10,
-            defense: 0,
-            attack: 0,
-            max_health: 10,
-            mana: 0,
-        })
-        .insert(EncounterTracker {
-            timer: Timer::from_seconds(1.0, true)
-        })
-        .insert(animation)
+        .insert(CombatStats::new_player())
+        .insert(Animator::default())
+        .insert(PlayerPath::default())
         .id();
 }
 
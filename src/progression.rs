@@ -0,0 +1,319 @@
+use std::fs::File;
+use ron::de::from_reader;
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_startup_system_to_stage(StartupStage::PreStartup, Self::load)
+            .init_resource::<TalentOffer>()
+            .add_system_set(
+                SystemSet::on_enter(World)
+                    .with_system(spawn_world_level_text)
+            )
+            .add_system_set(
+                SystemSet::on_update(World)
+                    .with_system(open_level_up.after(accept_reward))
+                    .with_system(update_world_level_text)
+            )
+            .add_system_set(
+                SystemSet::on_enter(LevelUp)
+                    .with_system(roll_talent_offer)
+                    .with_system(spawn_level_up_interface.after(roll_talent_offer))
+                    .with_system(bag_interface_camera)
+            )
+            .add_system_set(
+                SystemSet::on_update(LevelUp)
+                    .with_system(choose_talent)
+            )
+            .add_system_set(
+                SystemSet::on_exit(LevelUp)
+                    .with_system(despawn_bag_interface)
+                    .with_system(update_stats)
+            );
+    }
+}
+
+impl ProgressionPlugin {
+    fn load(mut commands: Commands) {
+        commands.insert_resource(Progression::load());
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum DiceKind {
+    Attack,
+    Mana,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum TalentEffect {
+    Stat(StatModifier),
+    /// One more combat deck card of that level.
+    DeckSlot(usize),
+    /// One more die rolled with the attack or mana die.
+    ExtraDice(DiceKind),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Talent {
+    pub name: String,
+    /// Index in the combat icon atlas.
+    pub icon: usize,
+    pub effect: TalentEffect,
+}
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct Progression {
+    pub xp_per_level: usize,
+    pub enemy_xp: usize,
+    pub event_xp: usize,
+    pub choices: usize,
+    pub talents: Vec<Talent>,
+}
+
+impl Progression {
+    pub fn load() -> Self {
+        let file = File::open("assets/Talents.ron").expect("Failed opening file");
+        from_reader(file).expect("Unable to load talents")
+    }
+
+    pub fn level(&self, xp: usize) -> usize {
+        let mut level = 1;
+        let mut needed = self.xp_per_level;
+        while needed > 0 && xp >= needed {
+            level += 1;
+            needed += self.xp_per_level * level;
+        }
+        level
+    }
+
+    /// Total experience the next level takes.
+    pub fn next_level_xp(&self, xp: usize) -> usize {
+        (1..=self.level(xp)).map(|l| self.xp_per_level * l).sum()
+    }
+
+    /// Level-ups the player has not picked a talent for yet.
+    pub fn pending_talents(&self, player: &Player) -> usize {
+        (self.level(player.xp) - 1).saturating_sub(player.talents.len())
+    }
+
+    pub fn enemy_reward(&self, enemy_lvl: usize) -> usize {
+        self.enemy_xp * std::cmp::max(enemy_lvl, 1)
+    }
+
+    pub fn event_reward(&self, event_lvl: usize) -> usize {
+        self.event_xp * std::cmp::max(event_lvl, 1)
+    }
+}
+
+pub fn extra_dice(player: &Player, kind: DiceKind) -> usize {
+    player.talents
+        .iter()
+        .filter(|t| **t == TalentEffect::ExtraDice(kind))
+        .count()
+}
+
+/// Talents shown on the level-up screen, as indexes in `Progression::talents`.
+#[derive(Default)]
+pub struct TalentOffer(pub Vec<usize>);
+
+#[derive(Component)]
+pub struct TalentChoice(pub usize);
+
+#[derive(Component)]
+pub struct WorldLevelText;
+
+#[derive(Component)]
+pub struct LevelUpText;
+
+pub fn open_level_up(
+    mut state: ResMut<State<GameState>>,
+    progression: Res<Progression>,
+    player_query: Query<(&Player, &PlayerPath)>,
+    reward_query: Query<Entity, With<WorldReward>>,
+) {
+    let (player, path) = player_query.single();
+    if path.is_walking() || !reward_query.is_empty() {
+        return;
+    }
+
+    if progression.pending_talents(player) > 0 {
+        let _ = state.set(LevelUp);
+    }
+}
+
+pub fn roll_talent_offer(
+    mut offer: ResMut<TalentOffer>,
+    mut rng: ResMut<GameRng>,
+    progression: Res<Progression>,
+) {
+    offer.0 = (0..progression.talents.len()).choose_multiple(&mut rng.world, progression.choices);
+}
+
+pub fn choose_talent(
+    selected_query: Query<(&TalentChoice, &Selected)>,
+    mut player_query: Query<&mut Player>,
+    mut state: ResMut<State<GameState>>,
+    progression: Res<Progression>,
+) {
+    let mut player = player_query.single_mut();
+    for (choice, selected) in selected_query.iter() {
+        if selected.selected {
+            let talent = &progression.talents[choice.0];
+            player.talents.push(talent.effect.clone());
+            println!("Talent chosen: {}", talent.name);
+            state.set(World).expect("Failed to change states");
+            return;
+        }
+    }
+}
+
+pub fn spawn_level_up_interface(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    progression: Res<Progression>,
+    offer: Res<TalentOffer>,
+    player_query: Query<&Player>,
+) {
+    let player = player_query.single();
+    let level = progression.level(player.xp) - progression.pending_talents(player) + 1;
+
+    let mut sprites = vec![
+        spawn_background_element(
+            &mut commands,
+            &texture_storage,
+            Some(Vec2::new(10., 11.5)),
+            Transform::from_xyz(7., -9., 600.),
+            "Level up interface background",
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -5., 700.),
+                scale: Vec3::new(0.012, 0.012, 0.),
+                ..default()
+            },
+            format!("Level {}", level),
+            "Level up text".to_string(),
+            LevelUpText,
+            PlayerMarker,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -13., 700.),
+                scale: Vec3::new(0.01, 0.01, 0.),
+                ..default()
+            },
+            "Choose a talent".to_string(),
+            "Info text".to_string(),
+            LevelUpText,
+            PlayerMarker,
+        ),
+    ];
+
+    for (i, index) in offer.0.iter().enumerate() {
+        let talent = &progression.talents[*index];
+        let x = 4.5 + 2.5 * i as f32;
+
+        let button = spawn_combat_icon(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(x, -8., 700.),
+                scale: Vec3::new(2., 2., 1.),
+                ..default()
+            },
+            talent.icon,
+            "Talent button",
+        );
+        commands
+            .entity(button)
+            .insert(Interactive)
+            .insert(Selected::default())
+            .insert(TalentChoice(*index));
+
+        sprites.push(button);
+        sprites.push(spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(x, -9.2, 700.),
+                scale: Vec3::new(0.008, 0.008, 0.),
+                ..default()
+            },
+            talent_text(talent),
+            "Talent text".to_string(),
+            LevelUpText,
+            PlayerMarker,
+        ));
+    }
+
+    let _ = commands
+        .spawn()
+        .insert(Transform::default())
+        .insert(GlobalTransform::default())
+        .insert(Name::new("Level up interface"))
+        .insert(BagInterface)
+        .push_children(&sprites)
+        .id();
+}
+
+fn talent_text(talent: &Talent) -> String {
+    let effect = match &talent.effect {
+        TalentEffect::Stat(m) => format!("+{} {:?}", m.value, m.stat),
+        TalentEffect::DeckSlot(level) => format!("+1 level {} card", level),
+        TalentEffect::ExtraDice(kind) => format!("+1 {:?} die", kind),
+    };
+    format!("{}\n{}", talent.name, effect)
+}
+
+pub fn spawn_world_level_text(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    progression: Res<Progression>,
+    player_query: Query<&Player>,
+) {
+    let player = player_query.single();
+
+    spawn_text(
+        &mut commands,
+        &texture_storage,
+        Transform {
+            translation: Vec3::new(15., -6.5, 205.0),
+            scale: Vec3::new(0.01, 0.01, 0.),
+            ..default()
+        },
+        level_text(&progression, player),
+        "World level text".to_string(),
+        WorldLevelText,
+        WorldPlayerStatsMarker,
+    );
+}
+
+pub fn update_world_level_text(
+    mut text_query: Query<&mut Text, With<WorldLevelText>>,
+    progression: Res<Progression>,
+    player_query: Query<&Player, Changed<Player>>,
+) {
+    if let Ok(player) = player_query.get_single() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = level_text(&progression, player);
+        }
+    }
+}
+
+fn level_text(progression: &Progression, player: &Player) -> String {
+    format!(
+        "Lvl {} Xp {}/{}",
+        progression.level(player.xp),
+        player.xp,
+        progression.next_level_xp(player.xp),
+    )
+}
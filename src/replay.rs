@@ -0,0 +1,371 @@
+use std::fs::{create_dir_all, File};
+use std::time::Duration;
+use bevy::app::AppExit;
+use bevy::ecs::event::Events;
+use bevy::input::InputSystem;
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+pub const REPLAY_DIR: &str = "replays";
+pub const REPLAY_VERSION: u32 = 1;
+pub const RECORD_ENV: &str = "WARLOCK_RECORD";
+pub const REPLAY_ENV: &str = "WARLOCK_REPLAY";
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // the headless runner inserts its replay before adding the plugins
+        let replay = app.world.remove_resource::<Replay>().unwrap_or_else(Replay::from_env);
+
+        // playback has to run on the recorded seed and map
+        if let ReplayMode::Playback = replay.mode {
+            app
+                .insert_resource(GameRng::new(replay.file.seed))
+                .insert_resource(MapSettings { name: replay.file.map.clone() });
+        }
+
+        app
+            .insert_resource(replay)
+            .init_resource::<GameClock>()
+            .add_system_to_stage(CoreStage::PreUpdate, replay_input.after(InputSystem))
+            .add_system_to_stage(CoreStage::PostUpdate, replay_record)
+            .add_system_to_stage(CoreStage::Last, replay_finish);
+    }
+}
+
+/// Frame delta used by gameplay timers, taken from the replay during playback.
+#[derive(Default)]
+pub struct GameClock {
+    delta: Duration,
+}
+
+impl GameClock {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    Off,
+    Record,
+    Playback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    LeftPress,
+    LeftRelease,
+    RightPress,
+    RightRelease,
+    Menu,
+    Resume,
+    /// Menu button pressed.
+    Button(ButtonAction),
+    /// Hero class picked on the class-select screen.
+    Class(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayInput {
+    pub frame: usize,
+    pub cursor: (f32, f32),
+    pub action: ReplayAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEndState {
+    pub position: (i32, i32),
+    pub health: isize,
+    pub max_health: isize,
+    pub days: usize,
+    pub deck: Vec<usize>,
+    pub combat_deck: Vec<usize>,
+    pub items: Vec<usize>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub seed: u64,
+    pub map: String,
+    pub deltas: Vec<f32>,
+    pub inputs: Vec<ReplayInput>,
+    pub end_state: Option<ReplayEndState>,
+}
+
+impl ReplayFile {
+    pub fn path(name: &str) -> String {
+        format!("{}/{}.ron", REPLAY_DIR, name)
+    }
+
+    pub fn write(&self, name: &str) -> Result<(), String> {
+        create_dir_all(REPLAY_DIR).map_err(|e| e.to_string())?;
+        let content = to_string_pretty(self, PrettyConfig::new()).map_err(|e| e.to_string())?;
+        std::fs::write(Self::path(name), content).map_err(|e| e.to_string())
+    }
+
+    pub fn read(name: &str) -> Result<Self, String> {
+        let file = File::open(Self::path(name)).map_err(|e| e.to_string())?;
+        let replay: ReplayFile = from_reader(file).map_err(|e| e.to_string())?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "unsupported replay version {}, expected {}", replay.version, REPLAY_VERSION
+            ));
+        }
+
+        Ok(replay)
+    }
+}
+
+pub struct Replay {
+    pub mode: ReplayMode,
+    pub name: String,
+    pub frame: usize,
+    pub next_input: usize,
+    pub in_menu: bool,
+    /// Class to apply on playback, taken by `replay_class`.
+    pub class: Option<usize>,
+    /// Whether playback ended on the recorded end state, set once it finishes.
+    pub matched: Option<bool>,
+    pub file: ReplayFile,
+}
+
+impl Replay {
+    fn new(mode: ReplayMode, name: &str) -> Self {
+        Self {
+            mode,
+            name: name.to_string(),
+            frame: 0,
+            next_input: 0,
+            in_menu: true,
+            class: None,
+            matched: None,
+            file: ReplayFile {
+                version: REPLAY_VERSION,
+                ..default()
+            },
+        }
+    }
+
+    pub fn playback(name: &str) -> Self {
+        let mut replay = Self::new(ReplayMode::Playback, name);
+        replay.file = ReplayFile::read(name)
+            .unwrap_or_else(|e| panic!("Failed to read replay {}: {}", name, e));
+        replay
+    }
+
+    pub fn from_env() -> Self {
+        if let Ok(name) = std::env::var(REPLAY_ENV) {
+            Self::playback(&name)
+        } else if let Ok(name) = std::env::var(RECORD_ENV) {
+            Self::new(ReplayMode::Record, &name)
+        } else {
+            Self::new(ReplayMode::Off, "")
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.mode == ReplayMode::Playback
+    }
+
+    /// Records a menu or class-select action, clicks on the ui aren't replayed.
+    pub fn record(&mut self, action: ReplayAction) {
+        if self.mode == ReplayMode::Record {
+            self.push(Vec2::ZERO, action);
+        }
+    }
+
+    fn push(&mut self, cursor: Vec2, action: ReplayAction) {
+        self.file.inputs.push(ReplayInput {
+            frame: self.frame,
+            cursor: (cursor.x, cursor.y),
+            action,
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn replay_input(
+    mut replay: ResMut<Replay>,
+    mut clock: ResMut<GameClock>,
+    time: Res<Time>,
+    mut buttons: ResMut<Input<MouseButton>>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut cursor_state: ResMut<CursorState>,
+    mut state: ResMut<State<GameState>>,
+    mut save_event: EventWriter<SaveEvent>,
+) {
+    if !replay.is_playing() {
+        clock.delta = Duration::from_secs_f32(time.delta_seconds());
+        return;
+    }
+
+    clock.delta = match replay.file.deltas.get(replay.frame) {
+        Some(delta) => Duration::from_secs_f32(*delta),
+        None => Duration::ZERO,
+    };
+
+    // the player can only watch during playback
+    buttons.reset(MouseButton::Left);
+    buttons.reset(MouseButton::Right);
+    keys.reset(KeyCode::Escape);
+
+    while let Some(input) = replay.file.inputs.get(replay.next_input).cloned() {
+        if input.frame != replay.frame {
+            break;
+        }
+        replay.next_input += 1;
+
+        let cursor = Vec2::new(input.cursor.0, input.cursor.1);
+        cursor_state.world_position = cursor.round();
+
+        match input.action {
+            ReplayAction::LeftPress => {
+                cursor_state.last_left_click_float = cursor;
+                cursor_state.last_left_click = cursor.round();
+                buttons.press(MouseButton::Left);
+            }
+            ReplayAction::RightPress => {
+                cursor_state.last_right_click = cursor.round();
+                buttons.press(MouseButton::Right);
+            }
+            ReplayAction::LeftRelease => buttons.release(MouseButton::Left),
+            ReplayAction::RightRelease => buttons.release(MouseButton::Right),
+            ReplayAction::Menu => {
+                let _ = state.set(Menu);
+            }
+            ReplayAction::Resume => {
+                let _ = state.set(World);
+            }
+            ReplayAction::Button(ButtonAction::Start) => {
+                let _ = state.set(ClassSelect);
+            }
+            ReplayAction::Button(ButtonAction::Resume) => {
+                let _ = state.set(World);
+            }
+            ReplayAction::Button(ButtonAction::Save) => save_event.send(SaveEvent::Save),
+            // loading a save stops the recording and the end is the last recorded frame
+            ReplayAction::Button(ButtonAction::Continue | ButtonAction::Exit) => {}
+            ReplayAction::Class(index) => replay.class = Some(index),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn replay_record(
+    mut replay: ResMut<Replay>,
+    clock: Res<GameClock>,
+    rng: Res<GameRng>,
+    map_settings: Res<MapSettings>,
+    buttons: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor_state: Res<CursorState>,
+    state: Res<State<GameState>>,
+    mut save_event: EventReader<SaveEvent>,
+) {
+    match replay.mode {
+        ReplayMode::Off => return,
+        ReplayMode::Playback => {
+            replay.frame += 1;
+            return;
+        }
+        ReplayMode::Record => {}
+    }
+
+    if save_event.iter().any(|e| e == &SaveEvent::Load) {
+        println!("replay recording stopped: a save was loaded");
+        replay.mode = ReplayMode::Off;
+        return;
+    }
+
+    if replay.frame == 0 {
+        replay.file.seed = rng.seed;
+        replay.file.map = map_settings.name.clone();
+    }
+
+    replay.file.deltas.push(clock.delta.as_secs_f32());
+
+    if buttons.just_pressed(MouseButton::Left) {
+        replay.push(cursor_state.last_left_click_float, ReplayAction::LeftPress);
+    }
+    if buttons.just_released(MouseButton::Left) {
+        replay.push(cursor_state.world_position, ReplayAction::LeftRelease);
+    }
+    if buttons.just_pressed(MouseButton::Right) {
+        replay.push(cursor_state.last_right_click, ReplayAction::RightPress);
+    }
+    if buttons.just_released(MouseButton::Right) {
+        replay.push(cursor_state.world_position, ReplayAction::RightRelease);
+    }
+
+    // menu buttons are recorded by their listeners, this covers leaving and coming back to the world
+    let in_menu = state.current() == &Menu || state.current() == &ClassSelect;
+    if keys.just_pressed(KeyCode::Escape) && !in_menu {
+        replay.push(cursor_state.world_position, ReplayAction::Menu);
+    }
+    if replay.in_menu && !in_menu {
+        replay.push(cursor_state.world_position, ReplayAction::Resume);
+    }
+    replay.in_menu = in_menu;
+
+    replay.frame += 1;
+}
+
+pub fn replay_finish(
+    mut replay: ResMut<Replay>,
+    // one handle for both, a reader next to a writer of the same event is a conflicting access
+    mut exit: ResMut<Events<AppExit>>,
+    player_query: Query<(&Player, &CombatStats, &Transform)>,
+    next_button_query: Query<&NextButton>,
+) {
+    let finished = match replay.mode {
+        ReplayMode::Off => return,
+        ReplayMode::Record => !exit.is_empty(),
+        ReplayMode::Playback => replay.frame >= replay.file.deltas.len(),
+    };
+
+    if !finished {
+        return;
+    }
+
+    let end_state = match (player_query.get_single(), next_button_query.get_single()) {
+        (Ok((player, stats, transform)), Ok(next_button)) => Some(ReplayEndState {
+            position: grid_position(transform.translation),
+            health: stats.health,
+            max_health: stats.max_health,
+            days: next_button.days,
+            deck: player.deck.iter().map(|c| c.id).collect(),
+            combat_deck: player.combat_deck.iter().map(|c| c.id).collect(),
+            items: player.items_bag.iter().map(|i| i.id).collect(),
+        }),
+        _ => None,
+    };
+
+    match replay.mode {
+        ReplayMode::Record => {
+            replay.file.end_state = end_state;
+            match replay.file.write(&replay.name) {
+                Ok(_) => println!("replay saved to {}", ReplayFile::path(&replay.name)),
+                Err(e) => println!("failed to save replay: {}", e),
+            }
+        }
+        _ => {
+            replay.matched = Some(replay.file.end_state == end_state);
+            if replay.file.end_state == end_state {
+                println!("replay {} finished, end state matches", replay.name);
+            } else {
+                println!("replay {} finished, end state differs", replay.name);
+                println!("recorded: {:?}", replay.file.end_state);
+                println!("replayed: {:?}", end_state);
+            }
+            exit.send(AppExit);
+        }
+    }
+
+    replay.mode = ReplayMode::Off;
+}
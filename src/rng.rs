@@ -0,0 +1,84 @@
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+pub const SEED_ENV: &str = "WARLOCK_SEED";
+
+const WORLD_STREAM: u64 = 0;
+const COMBAT_STREAM: u64 = 1;
+const LOOT_STREAM: u64 = 2;
+const MAP_STREAM: u64 = 3;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let rng = GameRng::from_env();
+        println!("run seed {}", rng.seed);
+
+        app.insert_resource(rng);
+    }
+}
+
+pub struct GameRng {
+    pub seed: u64,
+    pub world: ChaCha12Rng,
+    pub combat: ChaCha12Rng,
+    pub loot: ChaCha12Rng,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: u64,
+    pub world: u128,
+    pub combat: u128,
+    pub loot: u128,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            world: Self::stream(seed, WORLD_STREAM),
+            combat: Self::stream(seed, COMBAT_STREAM),
+            loot: Self::stream(seed, LOOT_STREAM),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let seed = match std::env::var(SEED_ENV) {
+            Ok(value) => value.parse().expect("WARLOCK_SEED must be an unsigned integer"),
+            Err(_) => thread_rng().gen(),
+        };
+
+        Self::new(seed)
+    }
+
+    /// Generator of the map for `seed`, apart from the run streams so saves don't track it.
+    pub fn map(seed: u64) -> ChaCha12Rng {
+        Self::stream(seed, MAP_STREAM)
+    }
+
+    fn stream(seed: u64, stream: u64) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        rng.set_stream(stream);
+        rng
+    }
+
+    pub fn state(&self) -> RngState {
+        RngState {
+            seed: self.seed,
+            world: self.world.get_word_pos(),
+            combat: self.combat.get_word_pos(),
+            loot: self.loot.get_word_pos(),
+        }
+    }
+
+    pub fn restore(state: &RngState) -> Self {
+        let mut rng = Self::new(state.seed);
+        rng.world.set_word_pos(state.world);
+        rng.combat.set_word_pos(state.combat);
+        rng.loot.set_word_pos(state.loot);
+        rng
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+/// Roamers chase the player seen within this many tiles.
+pub const ROAMER_SIGHT: usize = 4;
+/// Roamers never step further than this from their lair.
+pub const LAIR_RADIUS: usize = 5;
+
+pub struct RoamingPlugin;
+
+impl Plugin for RoamingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(World)
+                    .with_system(roam.after(next_day_button))
+                    .with_system(spawn_from_lairs.after(roam))
+                    .with_system(despawn_defeated_roamers)
+                    .with_system(roamer_visibility.after(update_fog))
+            )
+            .add_system_set(
+                SystemSet::on_exit(World)
+                    .with_system(hide_roamers)
+            );
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Roamer {
+    pub enemy_type: EnemyType,
+    pub lair: GridPos,
+    pub sprite_index: usize,
+}
+
+#[derive(Component, Clone)]
+pub struct Lair {
+    pub enemy_type: EnemyType,
+    pub sprite_index: usize,
+}
+
+impl Lair {
+    /// One roamer per lair early on, more as the enemies grow stronger.
+    pub fn capacity(enemy_buff: usize) -> usize {
+        1 + enemy_buff / 3
+    }
+}
+
+pub fn roamer_step<R: Rng>(
+    walkable: &HashSet<GridPos>,
+    occupied: &[GridPos],
+    roamer: &Roamer,
+    from: GridPos,
+    player: GridPos,
+    rng: &mut R,
+) -> GridPos {
+    let in_leash = |pos: &GridPos| grid_distance(*pos, roamer.lair) <= LAIR_RADIUS;
+    let is_free = |pos: &GridPos| !occupied.contains(pos);
+
+    if grid_distance(from, player) <= ROAMER_SIGHT {
+        if let Some(step) = find_path(walkable, from, player).and_then(|path| path.first().copied()) {
+            if in_leash(&step) && is_free(&step) {
+                return step;
+            }
+        }
+    }
+
+    let mut options = reachable_tiles(walkable, from, 1)
+        .into_iter()
+        .filter(|pos| in_leash(pos) && is_free(pos) && *pos != player)
+        .collect::<Vec<GridPos>>();
+    options.sort_unstable();
+    options.choose(rng).copied().unwrap_or(from)
+}
+
+pub fn roam(
+    mut new_day: EventReader<NewDayEvent>,
+    grid: Res<MapGrid>,
+    mut rng: ResMut<GameRng>,
+    player_query: Query<&Transform, With<Player>>,
+    mut roamer_query: Query<(&mut Transform, &Roamer), Without<Player>>,
+    encounter_query: Query<&Transform, (SpawnerFilter, Without<Roamer>)>,
+) {
+    if new_day.iter().count() == 0 {
+        return;
+    }
+
+    let player = grid_position(player_query.single().translation);
+    let mut occupied = encounter_query
+        .iter()
+        .map(|transform| grid_position(transform.translation))
+        .chain(roamer_query.iter().map(|(transform, _)| grid_position(transform.translation)))
+        .collect::<Vec<GridPos>>();
+
+    let mut roamers = roamer_query.iter_mut().collect::<Vec<_>>();
+    roamers.sort_by_key(|(transform, _)| grid_position(transform.translation));
+
+    for (transform, roamer) in roamers.iter_mut() {
+        let from = grid_position(transform.translation);
+        let to = roamer_step(&grid.walkable, &occupied, roamer, from, player, &mut rng.world);
+
+        if let Some(slot) = occupied.iter_mut().find(|pos| **pos == from) {
+            *slot = to;
+        }
+        transform.translation.x = to.0 as f32;
+        transform.translation.y = to.1 as f32;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_from_lairs(
+    mut commands: Commands,
+    mut new_day: EventReader<NewDayEvent>,
+    mut rng: ResMut<GameRng>,
+    texture_storage: Res<TextureStorage>,
+    next_button_query: Query<&NextButton>,
+    player_query: Query<&Transform, With<Player>>,
+    lair_query: Query<(&Transform, &Lair), Without<Player>>,
+    roamer_query: Query<(&Transform, &Roamer), Without<Player>>,
+) {
+    if new_day.iter().count() == 0 {
+        return;
+    }
+
+    let capacity = Lair::capacity(next_button_query.single().enemy_buff());
+    let player = grid_position(player_query.single().translation);
+
+    let mut lairs = lair_query
+        .iter()
+        .map(|(transform, lair)| (grid_position(transform.translation), lair.clone()))
+        .collect::<Vec<(GridPos, Lair)>>();
+    lairs.sort_by_key(|(pos, _)| *pos);
+
+    for (pos, lair) in lairs {
+        let alive = roamer_query.iter().filter(|(_, roamer)| roamer.lair == pos).count();
+        let blocked = pos == player
+            || roamer_query.iter().any(|(transform, _)| grid_position(transform.translation) == pos);
+
+        if alive < capacity && !blocked && rng.world.gen_bool(0.5) {
+            let roamer = Roamer {
+                enemy_type: lair.enemy_type,
+                lair: pos,
+                sprite_index: lair.sprite_index,
+            };
+            spawn_roamer(&mut commands, &texture_storage, roamer, pos);
+        }
+    }
+}
+
+pub fn despawn_defeated_roamers(
+    mut commands: Commands,
+    roamer_query: Query<(Entity, &EncounterType), With<Roamer>>,
+) {
+    for (entity, enc_type) in roamer_query.iter() {
+        if enc_type.1 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn roamer_visibility(
+    fog: Res<FogOfWar>,
+    mut roamer_query: Query<(&Transform, &mut Visibility), With<Roamer>>,
+) {
+    for (transform, mut visibility) in roamer_query.iter_mut() {
+        visibility.is_visible = fog.visible.contains(&grid_position(transform.translation));
+    }
+}
+
+pub fn hide_roamers(mut roamer_query: Query<&mut Visibility, With<Roamer>>) {
+    for mut visibility in roamer_query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
@@ -0,0 +1,199 @@
+use std::fs::{create_dir_all, File};
+use std::path::Path;
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+pub const SAVE_DIR: &str = "saves";
+pub const SAVE_PATH: &str = "saves/campaign.ron";
+pub const SAVE_VERSION: u32 = 5;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<SaveEvent>()
+            .add_system(save_game)
+            .add_system(load_game);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveEvent {
+    Save,
+    Load,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    pub version: u32,
+    #[serde(default)]
+    pub map: String,
+    pub player: Player,
+    pub stats: CombatStats,
+    pub position: (i32, i32),
+    pub days: usize,
+    pub move_dice: MoveDice,
+    pub item_pull: ItemPull,
+    pub visited_events: Vec<(i32, i32)>,
+    pub defeated_encounters: Vec<(i32, i32)>,
+    pub rng: RngState,
+    #[serde(default)]
+    pub explored: Vec<(i32, i32)>,
+    #[serde(default)]
+    pub roamers: Vec<(Roamer, (i32, i32))>,
+    #[serde(default)]
+    pub shop: ShopStock,
+}
+
+impl SaveFile {
+    pub fn exists() -> bool {
+        Path::new(SAVE_PATH).exists()
+    }
+
+    pub fn write(&self) -> Result<(), String> {
+        create_dir_all(SAVE_DIR).map_err(|e| e.to_string())?;
+        let content = to_string_pretty(self, PrettyConfig::new()).map_err(|e| e.to_string())?;
+        std::fs::write(SAVE_PATH, content).map_err(|e| e.to_string())
+    }
+
+    pub fn read() -> Result<Self, String> {
+        let file = File::open(SAVE_PATH).map_err(|e| e.to_string())?;
+        let save: SaveFile = from_reader(file).map_err(|e| e.to_string())?;
+
+        if save.version != SAVE_VERSION {
+            return Err(format!(
+                "unsupported save version {}, expected {}", save.version, SAVE_VERSION
+            ));
+        }
+
+        Ok(save)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn save_game(
+    mut save_event: EventReader<SaveEvent>,
+    player_query: Query<(&Player, &CombatStats, &Transform)>,
+    move_dice_query: Query<&MoveDice>,
+    next_button_query: Query<&NextButton>,
+    items: Res<ItemPull>,
+    shop: Res<ShopStock>,
+    rng: Res<GameRng>,
+    fog: Res<FogOfWar>,
+    map_settings: Res<MapSettings>,
+    event_query: Query<(&Transform, &WorldEvent), WorldEventFilter>,
+    encounter_query: Query<(&Transform, &EncounterType), SpawnerFilter>,
+    roamer_query: Query<(&Transform, &Roamer)>,
+    replay: Res<Replay>,
+) {
+    if !save_event.iter().any(|e| e == &SaveEvent::Save) {
+        return;
+    }
+    // a replayed save leaves the player's save file alone
+    if replay.is_playing() {
+        return;
+    }
+
+    let (player, stats, transform) = player_query.single();
+
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        map: map_settings.name.clone(),
+        player: player.clone(),
+        stats: stats.clone(),
+        position: grid_position(transform.translation),
+        days: next_button_query.single().days,
+        move_dice: move_dice_query.single().clone(),
+        item_pull: items.clone(),
+        visited_events: event_query
+            .iter()
+            .filter(|(_, event)| event.is_visited)
+            .map(|(transform, _)| grid_position(transform.translation))
+            .collect(),
+        defeated_encounters: encounter_query
+            .iter()
+            .filter(|(_, enc_type)| enc_type.1)
+            .map(|(transform, _)| grid_position(transform.translation))
+            .collect(),
+        rng: rng.state(),
+        explored: fog.explored.iter().copied().collect(),
+        roamers: roamer_query
+            .iter()
+            .map(|(transform, roamer)| (roamer.clone(), grid_position(transform.translation)))
+            .collect(),
+        shop: shop.clone(),
+    };
+
+    match save.write() {
+        Ok(_) => println!("game saved to {}", SAVE_PATH),
+        Err(e) => println!("failed to save game: {}", e),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn load_game(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    mut save_event: EventReader<SaveEvent>,
+    mut player_query: Query<(&mut Player, &mut CombatStats, &mut Transform)>,
+    mut move_dice_query: Query<&mut MoveDice>,
+    mut next_button_query: Query<&mut NextButton>,
+    mut items: ResMut<ItemPull>,
+    mut shop: ResMut<ShopStock>,
+    mut rng: ResMut<GameRng>,
+    mut fog: ResMut<FogOfWar>,
+    map_settings: Res<MapSettings>,
+    mut event_query: Query<(&Transform, &mut WorldEvent), WorldEventFilter>,
+    mut encounter_query: Query<(&Transform, &mut EncounterType), SpawnerFilter>,
+    roamer_query: Query<Entity, With<Roamer>>,
+) {
+    if !save_event.iter().any(|e| e == &SaveEvent::Load) {
+        return;
+    }
+
+    let save = match SaveFile::read() {
+        Ok(save) => save,
+        Err(e) => {
+            println!("failed to load game: {}", e);
+            return;
+        }
+    };
+
+    if save.map != map_settings.name {
+        println!("save was made on map {}, current map is {}", save.map, map_settings.name);
+        return;
+    }
+
+    let (mut player, mut stats, mut transform) = player_query.single_mut();
+    *player = save.player;
+    *stats = save.stats;
+    transform.translation.x = save.position.0 as f32;
+    transform.translation.y = save.position.1 as f32;
+
+    *move_dice_query.single_mut() = save.move_dice;
+    next_button_query.single_mut().days = save.days;
+    *items = save.item_pull;
+    *shop = save.shop;
+    *rng = GameRng::restore(&save.rng);
+    fog.explored = save.explored.into_iter().collect();
+    fog.sight_center = None;
+
+    for (transform, mut event) in event_query.iter_mut() {
+        event.is_visited = save.visited_events.contains(&grid_position(transform.translation));
+    }
+
+    for (transform, mut enc_type) in encounter_query.iter_mut() {
+        enc_type.1 = save.defeated_encounters.contains(&grid_position(transform.translation));
+    }
+
+    for entity in roamer_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (roamer, pos) in save.roamers {
+        spawn_roamer(&mut commands, &texture_storage, roamer, pos);
+    }
+}
@@ -0,0 +1,493 @@
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+pub const SHOP_ITEM_SLOTS: usize = 3;
+pub const SHOP_CARD_SLOTS: usize = 3;
+/// Gold paid per level of a reward, on top of the reward itself.
+pub const GOLD_PER_LEVEL: usize = 5;
+pub const PRICE_PER_LEVEL: usize = 10;
+/// Every this many days the shop stock goes up a level.
+pub const SHOP_LEVEL_DAYS: usize = 5;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ShopStock>()
+            .add_startup_system(init_shop_stock)
+            .add_system_set(
+                SystemSet::on_enter(World)
+                    .with_system(spawn_world_gold_text)
+            )
+            .add_system_set(
+                SystemSet::on_update(World)
+                    .with_system(open_town_shop.after(player_movement))
+                    .with_system(refresh_shop_stock.after(next_day_button))
+                    .with_system(update_world_gold_text)
+            )
+            .add_system_set(
+                SystemSet::on_enter(Shop)
+                    .with_system(spawn_shop_interface)
+                    .with_system(bag_interface_camera)
+            )
+            .add_system_set(
+                SystemSet::on_update(Shop)
+                    .with_system(shop_trade)
+                    .with_system(update_shop_goods.after(shop_trade))
+                    .with_system(shop_button_exit)
+                    .with_system(shop_button_forge)
+            )
+            .add_system_set(
+                SystemSet::on_exit(Shop)
+                    .with_system(despawn_bag_interface)
+            );
+    }
+}
+
+pub fn gold_reward(lvl: usize) -> usize {
+    GOLD_PER_LEVEL * lvl
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopOffer {
+    BuyItem,
+    BuyCard,
+    SellItem,
+    SellCard,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ShopGoods {
+    pub id: usize,
+    pub level: usize,
+    pub offer: ShopOffer,
+}
+
+impl ShopGoods {
+    /// Selling pays back half the buying price.
+    pub fn price(&self) -> usize {
+        match self.offer {
+            ShopOffer::BuyItem | ShopOffer::BuyCard => PRICE_PER_LEVEL * self.level,
+            ShopOffer::SellItem | ShopOffer::SellCard => PRICE_PER_LEVEL * self.level / 2,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ShopExit;
+
+#[derive(Component)]
+pub struct ShopGoldText;
+
+#[derive(Component)]
+pub struct WorldGoldText;
+
+#[derive(Component)]
+pub struct ShopInfoHint;
+
+#[derive(Component)]
+pub struct PriceText;
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct ShopStock {
+    pub items: Vec<ItemView>,
+    pub cards: Vec<CardView>,
+}
+
+impl ShopStock {
+    pub fn level(days: usize) -> usize {
+        std::cmp::min(1 + days / SHOP_LEVEL_DAYS, 3)
+    }
+
+    pub fn roll<R: Rng>(storage: &TemplateStorage, card_pool: &[usize], days: usize, rng: &mut R) -> Self {
+        let level = Self::level(days);
+        let pick = |templates: &[Template], slots: usize, rng: &mut R| {
+            templates
+                .iter()
+                .filter(|t| t.level <= level)
+                .collect::<Vec<&Template>>()
+                .choose_multiple(rng, slots)
+                .map(|t| (t.id, t.level))
+                .collect::<Vec<(usize, usize)>>()
+        };
+
+        Self {
+            items: pick(&storage.items, SHOP_ITEM_SLOTS, rng)
+                .into_iter()
+                .map(|(id, level)| ItemView { id, level, bonus: 0 })
+                .collect(),
+            cards: pick(&class_cards(storage, card_pool), SHOP_CARD_SLOTS, rng)
+                .into_iter()
+                .map(|(id, level)| CardView { id, level })
+                .collect(),
+        }
+    }
+
+    /// Reroll for a freshly chosen class.
+    pub fn reset(&mut self, storage: &TemplateStorage, card_pool: &[usize], rng: &mut GameRng) {
+        *self = Self::roll(storage, card_pool, 0, &mut rng.loot);
+    }
+
+    /// Moves the goods between the shop and the player, returns false if the deal can't be made.
+    pub fn trade(&mut self, goods: &ShopGoods, player: &mut Player) -> bool {
+        match goods.offer {
+            ShopOffer::BuyItem | ShopOffer::BuyCard if player.gold < goods.price() => return false,
+            ShopOffer::BuyItem => {
+                let index = match self.items.iter().position(|i| i.id == goods.id) {
+                    Some(index) => index,
+                    None => return false,
+                };
+                self.items.remove(index);
+                player.gold -= goods.price();
+                player.add_in_bag(goods.id, goods.level);
+            }
+            ShopOffer::BuyCard => {
+                let index = match self.cards.iter().position(|c| c.id == goods.id) {
+                    Some(index) => index,
+                    None => return false,
+                };
+                self.cards.remove(index);
+                player.gold -= goods.price();
+                player.add_in_deck(goods.id, goods.level);
+            }
+            ShopOffer::SellItem => {
+                let index = match player.items_bag.iter().position(|i| i.id == goods.id) {
+                    Some(index) => index,
+                    None => return false,
+                };
+                player.items_bag.remove(index);
+                player.gold += goods.price();
+            }
+            ShopOffer::SellCard => {
+                if !player.sell_card(goods.id) {
+                    return false;
+                }
+                player.gold += goods.price();
+            }
+        }
+        true
+    }
+}
+
+fn class_cards(storage: &TemplateStorage, card_pool: &[usize]) -> Vec<Template> {
+    storage.skill_cards
+        .iter()
+        .filter(|t| card_pool.contains(&t.id))
+        .cloned()
+        .collect()
+}
+
+pub fn init_shop_stock(
+    mut stock: ResMut<ShopStock>,
+    mut rng: ResMut<GameRng>,
+    template_storage: Res<TemplateStorage>,
+    classes: Res<HeroClasses>,
+) {
+    let card_pool = &classes.get("").cards;
+    stock.reset(&template_storage, card_pool, &mut rng);
+}
+
+pub fn refresh_shop_stock(
+    mut new_day: EventReader<NewDayEvent>,
+    mut stock: ResMut<ShopStock>,
+    mut rng: ResMut<GameRng>,
+    template_storage: Res<TemplateStorage>,
+    classes: Res<HeroClasses>,
+    next_button_query: Query<&NextButton>,
+    player_query: Query<&Player>,
+) {
+    if new_day.iter().count() == 0 {
+        return;
+    }
+
+    let days = next_button_query.single().days;
+    let card_pool = &classes.get(&player_query.single().class).cards;
+    *stock = ShopStock::roll(&template_storage, card_pool, days, &mut rng.loot);
+}
+
+/// Opens the shop when the player stops on a town tile, once per visit.
+pub fn open_town_shop(
+    mut state: ResMut<State<GameState>>,
+    mut last_position: Local<Option<GridPos>>,
+    player_query: Query<(&Transform, &PlayerPath), With<Player>>,
+    town_query: Query<&Transform, With<Town>>,
+) {
+    let (transform, path) = player_query.single();
+    let position = grid_position(transform.translation);
+
+    if !player_arrived(&mut last_position, path, position) {
+        return;
+    }
+
+    if town_query.iter().any(|t| grid_position(t.translation) == position) {
+        let _ = state.set(Shop);
+    }
+}
+
+/// True once the player stops on a new tile. The very first check only records where the game starts.
+pub fn player_arrived(last_position: &mut Option<GridPos>, path: &PlayerPath, position: GridPos) -> bool {
+    if path.is_walking() || *last_position == Some(position) {
+        return false;
+    }
+
+    let arrived = last_position.is_some();
+    *last_position = Some(position);
+    arrived
+}
+
+pub fn shop_button_exit(
+    selected_query: Query<&Selected, With<ShopExit>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    let selected = selected_query.single();
+    if selected.selected {
+        state.set(World).expect("Failed to change states");
+    }
+}
+
+pub fn shop_button_forge(
+    selected_query: Query<&Selected, With<ForgeButton>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    let selected = selected_query.single();
+    if selected.selected {
+        state.set(Forge).expect("Failed to change states");
+    }
+}
+
+pub fn shop_trade(
+    goods_query: Query<(&ShopGoods, &Selected)>,
+    mut player_query: Query<&mut Player>,
+    mut stock: ResMut<ShopStock>,
+    mut update_event: EventWriter<UpdateEvent>,
+) {
+    let mut player = player_query.single_mut();
+    for (goods, selected) in goods_query.iter() {
+        if selected.selected && stock.trade(goods, &mut player) {
+            update_event.send(UpdateEvent(true));
+        }
+    }
+}
+
+pub fn spawn_shop_interface(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    player_query: Query<&Player>,
+    mut update_event: EventWriter<UpdateEvent>,
+) {
+    let player = player_query.single();
+
+    let sprites = vec![
+        spawn_background_element(
+            &mut commands,
+            &texture_storage,
+            Some(Vec2::new(10., 11.5)),
+            Transform::from_xyz(7., -9., 600.),
+            "Shop interface background",
+        ),
+        spawn_world_interface_element(
+            &mut commands,
+            &texture_storage,
+            Transform::from_xyz(11., -4.5, 700.),
+            ShopExit,
+            Element::Exit,
+        ),
+        spawn_forge_button(&mut commands, &texture_storage),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(6., -4.5, 700.),
+                scale: Vec3::new(0.01, 0.01, 0.),
+                ..default()
+            },
+            format!("Gold {}", player.gold),
+            "Shop gold text".to_string(),
+            ShopGoldText,
+            PlayerMarker,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -8., 700.),
+                scale: Vec3::new(0.01, 0.01, 0.),
+                ..default()
+            },
+            "Sell".to_string(),
+            "Sell text".to_string(),
+            ShopInfoHint,
+            PlayerMarker,
+        ),
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(7., -14.2, 700.),
+                scale: Vec3::new(0.007, 0.007, 0.),
+                ..default()
+            },
+            "Click to buy or sell. Stock changes every day.\n\
+            The sword opens the forge. Mouse right click - cards info".to_string(),
+            "Info text".to_string(),
+            ShopInfoHint,
+            PlayerMarker,
+        ),
+    ];
+
+    update_event.send(UpdateEvent(true));
+
+    let _ = commands
+        .spawn()
+        .insert(Transform::default())
+        .insert(GlobalTransform::default())
+        .insert(Name::new("Shop interface"))
+        .insert(BagInterface)
+        .push_children(&sprites)
+        .id();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_shop_goods(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    template_storage: Res<TemplateStorage>,
+    stock: Res<ShopStock>,
+    player_query: Query<&Player>,
+    mut update_event: EventReader<UpdateEvent>,
+    interface_query: Query<Entity, With<BagInterface>>,
+    goods_query: Query<Entity, With<ShopGoods>>,
+    mut gold_text_query: Query<&mut Text, With<ShopGoldText>>,
+) {
+    if update_event.iter().count() == 0 {
+        return;
+    }
+
+    let player = player_query.single();
+    let interface = interface_query.single();
+
+    for entity in goods_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for mut text in gold_text_query.iter_mut() {
+        text.sections[0].value = format!("Gold {}", player.gold);
+    }
+
+    let stock_goods = stock.items
+        .iter()
+        .map(|i| ShopGoods { id: i.id, level: i.level, offer: ShopOffer::BuyItem })
+        .chain(stock.cards
+            .iter()
+            .map(|c| ShopGoods { id: c.id, level: c.level, offer: ShopOffer::BuyCard }));
+    let bag_goods = player.items_bag
+        .iter()
+        .map(|i| ShopGoods { id: i.id, level: i.level, offer: ShopOffer::SellItem });
+    let deck_goods = player.deck
+        .iter()
+        .map(|c| ShopGoods { id: c.id, level: c.level, offer: ShopOffer::SellCard });
+
+    let row = |y: f32| (0..6).map(move |i| Transform::from_xyz(3. + 1.5 * i as f32, y, 700.));
+
+    let mut sprites = Vec::new();
+    for (goods, transform) in stock_goods.zip(row(-6.))
+        .chain(bag_goods.zip(row(-9.5)))
+        .chain(deck_goods.zip(row(-11.5).chain(row(-13.))))
+    {
+        sprites.push(spawn_shop_goods(&mut commands, &texture_storage, &template_storage, goods, transform));
+    }
+
+    commands.entity(interface).push_children(&sprites);
+}
+
+fn spawn_forge_button(commands: &mut Commands, texture_storage: &TextureStorage) -> Entity {
+    let button = spawn_combat_icon(
+        commands,
+        texture_storage,
+        Transform {
+            translation: Vec3::new(9., -4.5, 700.),
+            scale: Vec3::new(2., 2., 1.),
+            ..default()
+        },
+        2,
+        "Forge button",
+    );
+
+    commands
+        .entity(button)
+        .insert(Interactive)
+        .insert(Selected::default())
+        .insert(ForgeButton);
+    button
+}
+
+fn spawn_shop_goods(
+    commands: &mut Commands,
+    texture_storage: &TextureStorage,
+    template_storage: &TemplateStorage,
+    goods: ShopGoods,
+    transform: Transform,
+) -> Entity {
+    let price = spawn_text(
+        commands,
+        texture_storage,
+        Transform {
+            translation: Vec3::new(0., -0.7, 1.),
+            scale: Vec3::new(0.007, 0.007, 0.),
+            ..default()
+        },
+        goods.price().to_string(),
+        "Price text".to_string(),
+        PriceText,
+        PlayerMarker,
+    );
+
+    let entity = match goods.offer {
+        ShopOffer::BuyItem | ShopOffer::SellItem => {
+            spawn_bag_item(commands, texture_storage, transform, template_storage, goods.id, true)
+        }
+        ShopOffer::BuyCard | ShopOffer::SellCard => {
+            spawn_spell_in_bag(commands, texture_storage, &transform, template_storage, goods.id, false)
+        }
+    };
+
+    commands
+        .entity(entity)
+        .insert(goods)
+        .push_children(&[price]);
+    entity
+}
+
+pub fn spawn_world_gold_text(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    player_query: Query<&Player>,
+) {
+    let player = player_query.single();
+
+    spawn_text(
+        &mut commands,
+        &texture_storage,
+        Transform {
+            translation: Vec3::new(15., -5.8, 205.0),
+            scale: Vec3::new(0.01, 0.01, 0.),
+            ..default()
+        },
+        format!("Gold {}", player.gold),
+        "World gold text".to_string(),
+        WorldGoldText,
+        WorldPlayerStatsMarker,
+    );
+}
+
+pub fn update_world_gold_text(
+    mut text_query: Query<&mut Text, With<WorldGoldText>>,
+    player_query: Query<&Player, Changed<Player>>,
+) {
+    if let Ok(player) = player_query.get_single() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = format!("Gold {}", player.gold);
+        }
+    }
+}
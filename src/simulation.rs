@@ -0,0 +1,172 @@
+use crate::combat::*;
+use crate::prelude::*;
+
+pub const MAX_SIMULATED_ROUNDS: usize = 100;
+
+pub struct SimulationSetup {
+    pub deck: Vec<usize>,
+    pub items: Vec<usize>,
+    pub days: usize,
+}
+
+pub struct FightResult {
+    pub won: bool,
+    pub rounds: usize,
+    pub hp_left: isize,
+}
+
+#[derive(Default)]
+pub struct SimulationReport {
+    pub enemy: String,
+    pub fights: usize,
+    pub wins: usize,
+    pub total_rounds: usize,
+    pub total_hp_left: isize,
+}
+
+impl SimulationReport {
+    pub fn add(&mut self, result: &FightResult) {
+        self.fights += 1;
+        self.total_rounds += result.rounds;
+        if result.won {
+            self.wins += 1;
+            self.total_hp_left += result.hp_left;
+        }
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        if self.fights == 0 { 0. } else { self.wins as f32 / self.fights as f32 }
+    }
+
+    pub fn average_rounds(&self) -> f32 {
+        if self.fights == 0 { 0. } else { self.total_rounds as f32 / self.fights as f32 }
+    }
+
+    pub fn average_hp_left(&self) -> f32 {
+        if self.wins == 0 { 0. } else { self.total_hp_left as f32 / self.wins as f32 }
+    }
+}
+
+pub fn simulated_player_stats(storage: &TemplateStorage, items: &[usize]) -> CombatStats {
+    let mut build = ItemBuild::default();
+
+    for id in items.iter() {
+        if let Some(item) = storage.items.iter().find(|t| t.id == *id) {
+            if let Some(slot) = item.item_slot() {
+                build.equip(slot, item.equipped());
+            }
+        }
+    }
+
+    let player = Player { item_build: build, ..default() };
+    let mut stats = CombatStats::new_player();
+    player.derive_stats(&mut stats, &storage.sets);
+    stats.health = stats.max_health;
+    stats
+}
+
+pub fn simulate_fight<R: Rng>(
+    storage: &TemplateStorage,
+    setup: &SimulationSetup,
+    enemy: &Template,
+    rng: &mut R,
+) -> FightResult {
+    let mut player = simulated_player_stats(storage, &setup.items);
+    let enemy_buff = NextButton { days: setup.days }.enemy_buff() as isize;
+    let mut enemy_stats = enemy_combat_stats(enemy, enemy_buff);
+    let abilities = enemy.enemy_abilities();
+    let mut cooldowns = vec![0; abilities.len()];
+    let policy = enemy.ability_policy.unwrap_or_default();
+
+    let cards = setup.deck
+        .iter()
+        .filter_map(|id| storage.skill_cards.iter().find(|t| t.id == *id))
+        .map(|t| CardView { id: t.id, level: t.level })
+        .collect::<Vec<CardView>>();
+    let mut deck = CombatDeck::new(&cards, rng);
+
+    let mut player_statuses = StatusEffects::default();
+    let mut enemy_statuses = StatusEffects::default();
+
+    let mut manager = CombatManager::default();
+    manager.init(&player);
+
+    while manager.round <= MAX_SIMULATED_ROUNDS {
+        deck.discard_hand();
+        deck.draw_hand(HAND_SIZE, rng);
+
+        if !manager.skip_round {
+            manager.roll_attack(roll_die(rng));
+            manager.roll_mana(roll_die(rng));
+
+            for view in deck.hand.clone() {
+                let card = storage.skill_cards.iter().find(|t| t.id == view.id).unwrap();
+                if manager.cast(
+                    card.mana_cost.unwrap_or(0),
+                    false,
+                    &card.card_effects(),
+                    &mut player,
+                    &mut player_statuses,
+                    Some(&mut enemy_statuses),
+                ) {
+                    deck.play(view.id);
+                }
+            }
+
+            let damage = enemy_statuses.absorb(pure_damage(manager.damage, enemy_stats.defense, 1));
+            if apply_damage(&mut enemy_stats, damage) {
+                return FightResult { won: true, rounds: manager.round, hp_left: player.health };
+            }
+        }
+
+        if !manager.enemy_skip_round && !enemy_statuses.stunned {
+            let (damage_amount, hits) = match choose_ability(&abilities, &mut cooldowns, policy, &enemy_stats, rng) {
+                Some(ability) => resolve_ability(
+                    ability, &mut enemy_stats, &mut manager, &mut enemy_statuses, &mut player_statuses,
+                ),
+                None => (enemy_stats.attack, 1),
+            };
+
+            let damage = player_statuses.absorb(pure_damage(damage_amount, manager.defense, hits));
+            if apply_damage(&mut player, damage) {
+                return FightResult { won: false, rounds: manager.round, hp_left: 0 };
+            }
+        }
+
+        player_statuses.round_end();
+        enemy_statuses.round_end();
+        manager.expire_buffs();
+        manager.next_round();
+
+        if player_statuses.round_start(&mut player) {
+            return FightResult { won: false, rounds: manager.round, hp_left: 0 };
+        }
+        if enemy_statuses.round_start(&mut enemy_stats) {
+            return FightResult { won: true, rounds: manager.round, hp_left: player.health };
+        }
+        if player_statuses.stunned {
+            manager.skip_round = true;
+        }
+    }
+
+    FightResult { won: false, rounds: MAX_SIMULATED_ROUNDS, hp_left: player.health }
+}
+
+pub fn simulate<R: Rng>(
+    storage: &TemplateStorage,
+    setup: &SimulationSetup,
+    enemy: &Template,
+    fights: usize,
+    rng: &mut R,
+) -> SimulationReport {
+    let mut report = SimulationReport {
+        enemy: enemy.name.clone(),
+        ..default()
+    };
+
+    for _ in 0..fights {
+        report.add(&simulate_fight(storage, setup, enemy, rng));
+    }
+
+    report
+}
@@ -3,7 +3,6 @@ use ron::de::from_reader;
 use crate::prelude::*;
 use serde::Deserialize;
 use crate::CardAction::Special;
-use crate::EnemyType::Lizard;
 use crate::EntityType::SkillCard;
 
 pub struct TemplatePlugin;
@@ -18,7 +17,9 @@ impl Plugin for TemplatePlugin {
 
 impl TemplatePlugin {
     fn load(commands: Commands) {
-        Templates::load().shuffle(commands);
+        let templates = Templates::load();
+        templates.check();
+        templates.shuffle(commands);
     }
 }
 
@@ -38,6 +39,118 @@ pub struct Template {
     pub health: Option<usize>,
     pub attack: Option<usize>,
     pub defense: Option<usize>,
+    pub effects: Option<Vec<CardEffect>>,
+    pub abilities: Option<Vec<EnemyAbility>>,
+    pub ability_policy: Option<AbilityPolicy>,
+    pub slot: Option<SlotKind>,
+    pub modifiers: Option<Vec<StatModifier>>,
+    pub set: Option<String>,
+    /// Name map files and encounters refer to an enemy by.
+    pub key: Option<String>,
+    pub sprite: Option<EnemySprite>,
+    /// Relative chance to come up as loot, 1 when missing.
+    pub loot_weight: Option<usize>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Template {
+    pub fn card_effects(&self) -> Vec<CardEffect> {
+        if let Some(effects) = &self.effects {
+            return effects.clone();
+        }
+
+        let value = EffectValue::Fixed(self.value.unwrap_or(0) as isize);
+        let kind = match self.card_action {
+            Some(CardAction::AttackBuff) => EffectKind::Damage,
+            Some(CardAction::DefenceBuff) => EffectKind::Defense,
+            Some(CardAction::ManaBuff) => EffectKind::Mana,
+            Some(CardAction::HealthBuff) => EffectKind::Heal,
+            _ => return vec![],
+        };
+        let rounds = match kind {
+            EffectKind::Damage | EffectKind::Defense | EffectKind::Mana => self.rounds.unwrap_or(1),
+            _ => 0,
+        };
+
+        vec![CardEffect { kind, value, rounds }]
+    }
+
+    /// Items without a slot keep the old one slot per buff layout.
+    pub fn item_slot(&self) -> Option<SlotKind> {
+        self.slot.or(match self.card_action {
+            Some(CardAction::AttackBuff) => Some(SlotKind::Weapon),
+            Some(CardAction::DefenceBuff) => Some(SlotKind::Body),
+            Some(CardAction::ManaBuff) => Some(SlotKind::OffHand),
+            Some(CardAction::HealthBuff) => Some(SlotKind::Amulet),
+            _ => None,
+        })
+    }
+
+    pub fn item_modifiers(&self) -> Vec<StatModifier> {
+        if let Some(modifiers) = &self.modifiers {
+            return modifiers.clone();
+        }
+
+        let stat = match self.card_action {
+            Some(CardAction::AttackBuff) => Stat::Attack,
+            Some(CardAction::DefenceBuff) => Stat::Defense,
+            Some(CardAction::ManaBuff) => Stat::Mana,
+            Some(CardAction::HealthBuff) => Stat::MaxHealth,
+            _ => return vec![],
+        };
+        vec![StatModifier { stat, value: self.value.unwrap_or(0) as isize }]
+    }
+
+    pub fn equipped(&self) -> EquippedItem {
+        EquippedItem {
+            id: self.id,
+            set: self.set.clone(),
+            modifiers: self.item_modifiers(),
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().flatten().any(|t| t == tag)
+    }
+
+    pub fn enemy_abilities(&self) -> Vec<EnemyAbility> {
+        self.abilities.clone().unwrap_or_else(|| vec![EnemyAbility {
+            name: "Attack".to_string(),
+            action: EnemyAction::Attack,
+            weight: 1,
+            hp_below: None,
+            cooldown: 0,
+        }])
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct BattlegroundSprite {
+    pub path: String,
+    pub color: (f32, f32, f32),
+    #[serde(default)]
+    pub flip: bool,
+    #[serde(skip)]
+    pub handle: Handle<Image>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct EnemySprite {
+    pub path: String,
+    pub tile: f32,
+    pub columns: usize,
+    pub rows: usize,
+    /// Size of the enemy in world units.
+    pub size: (f32, f32),
+    /// Where the enemy stands in the first slot.
+    pub position: (f32, f32),
+    /// Attack text under the enemy, defense and health follow below it.
+    pub stats_position: (f32, f32),
+    /// Set in `assets/Animations.ron` with idle, attack, hurt and death states.
+    pub animations: String,
+    pub battleground: BattlegroundSprite,
+    #[serde(skip)]
+    pub handle: Handle<TextureAtlas>,
 }
 
 #[derive(Clone, Deserialize, Debug, PartialEq, Eq)]
@@ -50,16 +163,24 @@ pub enum EntityType {
 #[derive(Clone, Deserialize, Debug, Default)]
 pub struct Templates {
     pub entities: Vec<Template>,
+    #[serde(default)]
+    pub sets: Vec<ItemSet>,
+    #[serde(default)]
+    pub loot_excluded_tags: Vec<String>,
 }
 
 
 impl Templates {
     pub fn load() -> Self {
         let file = File::open("assets/Templates.ron").expect("Failed opening file");
-        from_reader(file).expect("Unable to load templates")
+        from_reader(file).unwrap_or_else(|e| panic!("Unable to load templates: {}", e))
     }
 
     pub fn shuffle(&self, mut commands: Commands) {
+        commands.insert_resource(self.storage());
+    }
+
+    pub fn storage(&self) -> TemplateStorage {
         let mut skill_cards = Vec::new();
         let mut enemies = Vec::new();
         let mut items = Vec::new();
@@ -85,12 +206,14 @@ impl Templates {
             .map(|t| (t.id, t.entity_type.clone(), t.sprite_index))
             .collect::<Vec<(usize, EntityType, Option<usize>)>>();
 
-        commands.insert_resource(TemplateStorage {
+        TemplateStorage {
             skill_cards,
             enemies,
             items,
-            types
-        });
+            types,
+            sets: self.sets.clone(),
+            loot_excluded_tags: self.loot_excluded_tags.clone(),
+        }
     }
 }
 
@@ -99,18 +222,29 @@ pub struct TemplateStorage {
     pub enemies: Vec<Template>,
     pub items: Vec<Template>,
     pub types: Vec<(usize, EntityType, Option<usize>)>,
+    pub sets: Vec<ItemSet>,
+    pub loot_excluded_tags: Vec<String>,
 }
 
 impl TemplateStorage {
-    pub fn get_enemy(&self, enemy_type: EnemyType) -> Option<&Template> {
-        match enemy_type {
-            Lizard => self.enemies.iter().find(|e| e.id == 15),
-            EnemyType::Medusa => self.enemies.iter().find(|e| e.id == 16),
-            EnemyType::SmallDragon => self.enemies.iter().find(|e| e.id == 17),
-            EnemyType::Gin => self.enemies.iter().find(|e| e.id == 18),
-            EnemyType::BigDragon => self.enemies.iter().find(|e| e.id == 19),
-            EnemyType::Demon => self.enemies.iter().find(|e| e.id == 20),
-        }
+    pub fn get_enemy(&self, enemy_type: &EnemyType) -> Option<&Template> {
+        self.enemies
+            .iter()
+            .find(|e| e.key.as_ref() == Some(&enemy_type.0))
+    }
+
+    pub fn enemy_sprite(&self, enemy_type: &EnemyType) -> &EnemySprite {
+        self.get_enemy(enemy_type)
+            .and_then(|e| e.sprite.as_ref())
+            .unwrap_or_else(|| panic!("No sprite for enemy {}", enemy_type.0))
+    }
+
+    /// Skill cards and items that can drop as loot.
+    pub fn loot(&self) -> impl Iterator<Item = &Template> {
+        self.skill_cards
+            .iter()
+            .chain(self.items.iter())
+            .filter(|t| !self.loot_excluded_tags.iter().any(|tag| t.has_tag(tag)))
     }
 }
 
@@ -123,6 +257,83 @@ pub enum CardAction {
     Special,
 }
 
+#[derive(Clone, Deserialize, Debug, PartialEq, Eq)]
+pub struct CardEffect {
+    pub kind: EffectKind,
+    #[serde(default)]
+    pub value: EffectValue,
+    #[serde(default = "CardEffect::default_rounds")]
+    pub rounds: usize,
+}
+
+impl CardEffect {
+    fn default_rounds() -> usize {
+        1
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+pub enum EffectKind {
+    SkipEnemyTurn,
+    Damage,
+    /// Replaces the rest-of-fight damage buff instead of adding to it.
+    SetDamage,
+    Defense,
+    Mana,
+    Heal,
+    MaxHealth,
+    Status(StatusKind),
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+pub enum EffectValue {
+    Fixed(isize),
+    Round,
+}
+
+impl Default for EffectValue {
+    fn default() -> Self {
+        EffectValue::Fixed(0)
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, PartialEq, Eq)]
+pub struct EnemyAbility {
+    pub name: String,
+    pub action: EnemyAction,
+    #[serde(default = "EnemyAbility::default_weight")]
+    pub weight: usize,
+    #[serde(default)]
+    pub hp_below: Option<usize>,
+    /// Enemy turns the ability sits out after being used.
+    #[serde(default)]
+    pub cooldown: usize,
+}
+
+impl EnemyAbility {
+    fn default_weight() -> usize {
+        1
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+pub enum EnemyAction {
+    Attack,
+    MultiHit(usize),
+    Buff(isize),
+    Heal(isize),
+    DrainMana(isize),
+    SkipPlayerTurn,
+    Status(StatusEffect),
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum AbilityPolicy {
+    #[default]
+    WeightedRandom,
+    HpThreshold,
+}
+
 impl Default for CardAction {
     fn default() -> Self {
         Special
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use crate::prelude::*;
+
+/// Sprites per atlas, matching the grids in `GraphicsPlugin::load_graphics`.
+pub const CARD_SPRITES: usize = 16;
+pub const HINT_SPRITES: usize = 16;
+pub const ITEM_SPRITES: usize = 12;
+/// States combat plays on every enemy.
+pub const ENEMY_STATES: [&str; 4] = ["idle", "attack", "hurt", "death"];
+/// Levels world events hand out loot for.
+pub const LOOT_LEVELS: [usize; 3] = [1, 2, 3];
+
+impl Templates {
+    /// Problems that would otherwise surface as panics deep in gameplay, one line each.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut ids: HashMap<usize, &str> = HashMap::new();
+        let mut keys: HashMap<&str, &str> = HashMap::new();
+
+        for t in self.entities.iter() {
+            let mut error = |field: &str| errors.push(format!("{:?} {} \"{}\": {}", t.entity_type, t.id, t.name, field));
+
+            if let Some(other) = ids.insert(t.id, &t.name) {
+                error(&format!("id is already used by \"{}\"", other));
+            }
+            if t.loot_weight == Some(0) {
+                error("loot_weight must be at least 1");
+            }
+
+            match t.entity_type {
+                EntityType::Enemy => {
+                    for (field, value) in [("health", t.health), ("attack", t.attack), ("defense", t.defense)] {
+                        if value.is_none() {
+                            error(&format!("missing {}", field));
+                        }
+                    }
+                    match &t.key {
+                        None => error("missing key"),
+                        Some(key) => if let Some(other) = keys.insert(key.as_str(), &t.name) {
+                            error(&format!("key \"{}\" is already used by \"{}\"", key, other));
+                        }
+                    }
+                    if t.sprite.is_none() {
+                        error("missing sprite");
+                    }
+                }
+                EntityType::SkillCard => {
+                    if t.mana_cost.is_none() {
+                        error("missing mana_cost");
+                    }
+                    if t.value.is_none() {
+                        error("missing value");
+                    }
+                    if t.card_action.is_none() {
+                        error("missing card_action");
+                    }
+                    check_sprite(&mut error, "sprite_index", t.sprite_index, CARD_SPRITES);
+                    check_sprite(&mut error, "sub_sprite_index", t.sub_sprite_index, HINT_SPRITES);
+                }
+                EntityType::Item => {
+                    if t.item_slot().is_none() {
+                        error("no slot, and no non-Special card_action to take it from");
+                    }
+                    if t.item_modifiers().is_empty() {
+                        error("no modifiers, and no non-Special card_action to take them from");
+                    }
+                    if let Some(set) = &t.set {
+                        if !self.sets.iter().any(|s| &s.name == set) {
+                            error(&format!("set \"{}\" is not in the sets list", set));
+                        }
+                    }
+                    check_sprite(&mut error, "sprite_index", t.sprite_index, ITEM_SPRITES);
+                }
+            }
+        }
+
+        // slots of the same kind, like the two rings, are reported once
+        for (index, slot) in EQUIPMENT_SLOTS.iter().enumerate() {
+            if EQUIPMENT_SLOTS[..index].iter().any(|s| s.kind == slot.kind) {
+                continue;
+            }
+            let fits = self.entities
+                .iter()
+                .any(|t| t.entity_type == EntityType::Item && t.item_slot() == Some(slot.kind));
+            if !fits {
+                errors.push(format!("Equipment: no item fits the {} slot", slot.name));
+            }
+        }
+
+        let pull = ItemPull::new(&self.storage());
+        for level in LOOT_LEVELS {
+            if pull.pool(level).is_empty() {
+                errors.push(format!("Loot: no skill card or item of level {} can drop", level));
+            }
+        }
+
+        errors
+    }
+
+    /// Enemy animation sets that are missing a state or run off their sheet.
+    pub fn validate_animations(&self, library: &AnimationLibrary) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for t in self.entities.iter() {
+            let sprite = match &t.sprite {
+                Some(sprite) => sprite,
+                None => continue,
+            };
+            let frames = sprite.columns * sprite.rows;
+
+            for state in ENEMY_STATES {
+                let mut error = |field: &str| errors.push(format!("{:?} {} \"{}\": {}", t.entity_type, t.id, t.name, field));
+
+                match library.clip(&sprite.animations, state) {
+                    None => error(&format!("animation set \"{}\" has no {} state", sprite.animations, state)),
+                    Some(clip) => if let Some(frame) = clip.frames.iter().find(|f| **f >= frames) {
+                        error(&format!("{} frame {} is outside the {} frame sheet", state, frame, frames));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Stops the game with every problem listed instead of a panic mid-game.
+    pub fn check(&self) {
+        let library = AnimationLibrary::load();
+        let mut errors = self.validate();
+        errors.extend(library.validate());
+        errors.extend(self.validate_animations(&library));
+        if errors.is_empty() {
+            return;
+        }
+
+        for error in errors.iter() {
+            println!("{}", error);
+        }
+        panic!("assets/Templates.ron and assets/Animations.ron have {} errors", errors.len());
+    }
+}
+
+impl TemplateStorage {
+    /// Enemies the map places that have no template, and tiles the start can't walk to.
+    pub fn validate_map(&self, map: &MapFile) -> Vec<String> {
+        let mut errors = map.enemy_types()
+            .filter(|enemy_type| self.get_enemy(enemy_type).is_none())
+            .map(|enemy_type| format!("Map: no enemy template has key \"{}\"", enemy_type.0))
+            .collect::<Vec<String>>();
+
+        let walkable = map.walkable();
+        if !walkable.contains(&START_TILE) {
+            errors.push(format!("Map: start tile {:?} is not walkable", START_TILE));
+        } else {
+            let mut reached = reachable_tiles(&walkable, START_TILE, usize::MAX);
+            reached.insert(START_TILE);
+            let mut unreached = walkable.difference(&reached).collect::<Vec<&GridPos>>();
+            unreached.sort();
+            errors.extend(unreached.iter().map(|pos| format!("Map: tile {:?} can't be reached from the start", pos)));
+        }
+
+        errors
+    }
+}
+
+fn check_sprite(error: &mut impl FnMut(&str), field: &str, index: Option<usize>, len: usize) {
+    match index {
+        None => error(&format!("missing {}", field)),
+        Some(index) if index >= len => error(&format!("{} {} is outside the {} sprite atlas", field, index, len)),
+        Some(_) => {}
+    }
+}
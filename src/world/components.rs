@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
 use crate::CardAction::Special;
 use crate::prelude::*;
 
@@ -5,7 +7,7 @@ use crate::prelude::*;
 #[derive(Debug)]
 pub struct UpdateEvent(pub bool);
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Hash, Copy)]
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
 pub enum WorldEventType {
     Camp,
     Ruins,
@@ -24,6 +26,8 @@ pub struct SkillPackExit;
 #[derive(Component)]
 pub struct SkillPack;
 
+pub struct NewDayEvent;
+
 #[derive(Component)]
 pub struct NextButton {
     pub days: usize
@@ -110,25 +114,36 @@ pub struct WorldPlayerStatsMarker;
 #[derive(Component)]
 pub struct NonInteractiveItem;
 
-#[derive(Component, Default, Reflect)]
-#[reflect(Component)]
-pub struct EncounterTracker {
-    pub timer: Timer,
-}
+/// Worn item shown in the bag, holding its `EQUIPMENT_SLOTS` index.
+#[derive(Component)]
+pub struct EquippedSlot(pub usize);
+
+/// Position of a bag item sprite in `Player::items_bag`.
+#[derive(Component)]
+pub struct BagSlot(pub usize);
 
-#[derive(Clone, Copy, Default, Inspectable)]
+#[derive(Component)]
+pub struct SlotLabel;
+
+#[derive(Component)]
+pub struct SetBonusText;
+
+#[derive(Clone, Copy, Default, Inspectable, Serialize, Deserialize)]
 pub struct ItemView {
     pub id: usize,
     pub level: usize,
+    /// Forge upgrades kept by an item taken off.
+    #[serde(default)]
+    pub bonus: isize,
 }
 
-#[derive(Clone, Copy, Default, Inspectable, Component)]
+#[derive(Clone, Copy, Default, Inspectable, Component, Serialize, Deserialize)]
 pub struct CardView {
     pub id: usize,
     pub level: usize,
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct MoveDice {
     pub value: isize,
     pub can_roll: bool,
@@ -141,39 +156,60 @@ pub struct WorldEvent {
     pub is_visited: bool,
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
 pub struct ItemPull {
-    low_lvl: Vec<usize>,
-    middle_lvl: Vec<usize>,
-    high_lvl: Vec<usize>,
+    /// Shuffled loot ids by level, the next drop is at the back.
+    pools: BTreeMap<usize, Vec<usize>>,
 }
 
 impl ItemPull {
-    pub fn create_item_pull(mut commands: Commands) {
-        let mut pull = Self {
-            low_lvl: vec![2, 3, 4, 6, 9, 10, 24, 25, 26],//start with 1,5
-            middle_lvl: vec![7, 8, 11, 12, 27, 28, 29, 30],
-            high_lvl: vec![13, 14, 15, 16, 21, 22, 23],
-        };
-        pull.shuffle();
+    /// Every lootable template by level, in template order.
+    pub fn new(storage: &TemplateStorage) -> Self {
+        let mut pools = BTreeMap::new();
+        for t in storage.loot() {
+            pools.entry(t.level).or_insert_with(Vec::new).push(t.id);
+        }
+
+        Self { pools }
+    }
+
+    pub fn create_item_pull(mut commands: Commands, storage: &TemplateStorage, rng: &mut GameRng) {
+        let mut pull = Self::new(storage);
+        pull.shuffle(storage, rng);
 
         commands.insert_resource(pull);
     }
 
-    fn shuffle(&mut self) {
-        self.low_lvl.shuffle(&mut thread_rng());
-        self.middle_lvl.shuffle(&mut thread_rng());
-        self.high_lvl.shuffle(&mut thread_rng());
+    pub fn pool(&self, lvl: usize) -> &[usize] {
+        self.pools.get(&lvl).map(|p| p.as_slice()).unwrap_or(&[])
     }
 
-    pub fn get_item(&mut self, lvl: usize) -> Option<usize> {
-        match lvl {
-            1 => self.low_lvl.pop(),
-            2 => self.middle_lvl.pop(),
-            3 => self.high_lvl.pop(),
-            _ => None
+    /// Weighted shuffle, heavier templates tend to end up nearer the back.
+    fn shuffle(&mut self, storage: &TemplateStorage, rng: &mut GameRng) {
+        let weight = |id: usize| {
+            storage
+                .loot()
+                .find(|t| t.id == id)
+                .and_then(|t| t.loot_weight)
+                .unwrap_or(1)
+        };
+
+        for pool in self.pools.values_mut() {
+            let mut keyed = pool
+                .iter()
+                .map(|id| (rng.loot.gen::<f64>().powf(1. / weight(*id) as f64), *id))
+                .collect::<Vec<(f64, usize)>>();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            *pool = keyed.into_iter().map(|(_, id)| id).collect();
         }
     }
+
+    /// Takes the next reward of that level the filter lets through.
+    pub fn get_item(&mut self, lvl: usize, allowed: impl Fn(usize) -> bool) -> Option<usize> {
+        let pull = self.pools.get_mut(&lvl)?;
+        let index = pull.iter().rposition(|id| allowed(*id))?;
+        Some(pull.remove(index))
+    }
 }
 
 #[derive(Component, Inspectable)]
@@ -182,8 +218,10 @@ pub struct Item {
     pub lvl: usize,
     pub name: String,
     pub sprite_index: Option<usize>,
-    pub value: usize,
-    pub buff_type: CardAction
+    #[inspectable(ignore)]
+    pub slot: SlotKind,
+    #[inspectable(ignore)]
+    pub modifiers: Vec<StatModifier>,
 }
 
 #[derive(Component, Inspectable, Clone)]
@@ -196,6 +234,8 @@ pub struct Card {
     pub sprite_index: usize,
     pub rounds: usize,
     pub is_used: bool,
+    #[inspectable(ignore)]
+    pub effects: Vec<CardEffect>,
 }
 
 impl Default for Card {
@@ -209,6 +249,7 @@ impl Default for Card {
             sprite_index: 0,
             rounds: 1,
             is_used: false,
+            effects: vec![],
         }
     }
 }
@@ -10,11 +10,10 @@ pub struct WorldPlugin;
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_startup_system_to_stage(
-                StartupStage::PreStartup, Self::init_item_pull,
-            )
+            .add_startup_system(Self::init_item_pull.before(init_shop_stock))
             .add_event::<UpdateEvent>()
             .add_event::<EncounterEvent>()
+            .add_event::<NewDayEvent>()
             .add_system_set(
                 SystemSet::on_enter(World)
                     .with_system(show_buttons)
@@ -50,6 +49,9 @@ impl Plugin for WorldPlugin {
                 SystemSet::on_update(BagPack)
                     .with_system(bag_button_exit.after(take_item))
                     .with_system(take_item)
+                    .with_system(unequip_item)
+                    .with_system(update_bag_items.after(take_item).after(unequip_item))
+                    .with_system(update_set_bonus_text.after(take_item).after(unequip_item))
             )
             .add_system_set(
                 SystemSet::on_exit(BagPack)
@@ -77,7 +79,7 @@ impl Plugin for WorldPlugin {
 }
 
 impl WorldPlugin {
-    fn init_item_pull(commands: Commands) {
-        ItemPull::create_item_pull(commands);
+    fn init_item_pull(commands: Commands, mut rng: ResMut<GameRng>, template_storage: Res<TemplateStorage>) {
+        ItemPull::create_item_pull(commands, &template_storage, &mut rng);
     }
 }
@@ -34,12 +34,16 @@ pub fn town_event(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn world_object_event(
     mut commands: Commands,
     mut items: ResMut<ItemPull>,
+    mut rng: ResMut<GameRng>,
     mut player_query: Query<&mut Player>,
     texture_storage: Res<TextureStorage>,
     template_storage: Res<TemplateStorage>,
+    progression: Res<Progression>,
+    classes: Res<HeroClasses>,
     mut player_stats_query: Query<(&Transform, &mut CombatStats), With<Player>>,
     mut event_query: Query<(&Transform, &mut WorldEvent), WorldEventFilter>,
 ) {
@@ -50,14 +54,19 @@ pub fn world_object_event(
     for (transform, mut event) in event_query.iter_mut() {
         if collide_check(transform.translation, player_translation) && !event.is_visited {
             event.is_visited = true;
+            player.xp += progression.event_reward(event.lvl);
 
-            if let Some(reward) = get_reward_template(&template_storage, event.lvl, &mut items) {
+            let card_pool = &classes.get(&player.class).cards;
+            if let Some(reward) = get_reward_template(
+                &template_storage, event.lvl, &mut items, &mut rng, card_pool,
+            ) {
                 match event.event_type {
                     WorldEventType::Camp => add_reward(&reward, &mut player),
                     WorldEventType::Ruins => add_reward(&reward, &mut player),
                     WorldEventType::Altar => {
                         add_reward(&reward, &mut player);
-                        player_stats.max_health = std::cmp::max(player_stats.max_health - 1, 5_isize);
+                        player.altar_penalty += 1;
+                        player.derive_stats(&mut player_stats, &template_storage.sets);
                     }
                 }
 
@@ -137,6 +146,7 @@ pub fn show_buttons(
 }
 
 pub fn add_reward(reward: &Reward, player: &mut Player) {
+    player.gold += gold_reward(reward.item_lvl);
     match reward.entity_type {
         EntityType::SkillCard => player.add_in_deck(reward.item_id, reward.item_lvl),
         EntityType::Item => player.add_in_bag(reward.item_id, reward.item_lvl),
@@ -147,14 +157,17 @@ pub fn add_reward(reward: &Reward, player: &mut Player) {
 pub fn get_reward_template(
     template_storage: &Res<TemplateStorage>,
     mut lvl: usize,
-    items: &mut ItemPull
+    items: &mut ItemPull,
+    rng: &mut GameRng,
+    card_pool: &[usize],
 ) -> Option<Reward> {
 
     if lvl == 0 {
-        lvl = thread_rng().gen_range(1..=3);
+        lvl = rng.loot.gen_range(1..=3);
     }
 
-    let template = if let Some(id) = items.get_item(lvl) {
+    let allowed = |id| class_allows(template_storage, card_pool, id);
+    let template = if let Some(id) = items.get_item(lvl, allowed) {
         template_storage.types
             .iter()
             .find(|t| t.0 == id)
@@ -296,115 +309,115 @@ pub fn update_spell_position(
 }
 
 pub fn take_item(
-    mut selected_query: Query<(&mut Item, &Selected, Entity)>,
-    items_in_player_query: Query<(&Item, Entity), NonInteractiveItemFilter>,
+    selected_query: Query<(&BagSlot, &Selected), With<Item>>,
     mut player_query: Query<&mut Player>,
-    mut commands: Commands,
-    texture_storage: Res<TextureStorage>,
     template_storage: Res<TemplateStorage>,
-    bag_interface_query: Query<Entity, With<BagInterface>>,
+    mut update_event: EventWriter<UpdateEvent>,
 ) {
     let mut player = player_query.single_mut();
-    let bag_interface = bag_interface_query.single();
-    for (item, selected, entity) in selected_query.iter_mut() {
-        if selected.selected {
-            let itm = match item.buff_type {
-                CardAction::DefenceBuff => {
-                    if let Some(val) = player.item_build.defense {
-                        if val.1 > item.value {
-                            return;
-                        }
-                    }
+    for (slot, selected) in selected_query.iter() {
+        if selected.selected && player.equip_from_bag(slot.0, &template_storage) {
+            update_event.send(UpdateEvent(true));
+        }
+    }
+}
 
-                    player.item_build.defense = Some((item.id, item.value));
-                    spawn_bag_item(
-                        &mut commands,
-                        &texture_storage,
-                        Transform::from_xyz(5., -6.5, 700.),
-                        &template_storage,
-                        item.id,
-                        false,
-                    )
-                }
-                CardAction::AttackBuff => {
-                    if let Some(val) = player.item_build.attack {
-                        if val.1 > item.value {
-                            return;
-                        }
-                    }
+pub fn unequip_item(
+    selected_query: Query<(&EquippedSlot, &Selected), With<Item>>,
+    mut player_query: Query<&mut Player>,
+    template_storage: Res<TemplateStorage>,
+    mut update_event: EventWriter<UpdateEvent>,
+) {
+    let mut player = player_query.single_mut();
+    for (slot, selected) in selected_query.iter() {
+        if selected.selected && player.unequip(slot.0, &template_storage) {
+            update_event.send(UpdateEvent(true));
+        }
+    }
+}
 
-                    player.item_build.attack = Some((item.id, item.value));
-                    spawn_bag_item(
-                        &mut commands,
-                        &texture_storage,
-                        Transform::from_xyz(6., -6.5, 700.),
-                        &template_storage,
-                        item.id,
-                        false,
-                    )
-                }
-                CardAction::ManaBuff => {
-                    if let Some(val) = player.item_build.mana {
-                        if val.1 > item.value {
-                            return;
-                        }
-                    }
+/// Respawns bag and equipped item sprites after the player changed them.
+pub fn update_bag_items(
+    mut commands: Commands,
+    texture_storage: Res<TextureStorage>,
+    template_storage: Res<TemplateStorage>,
+    player_query: Query<&Player>,
+    mut update_event: EventReader<UpdateEvent>,
+    interface_query: Query<Entity, With<BagInterface>>,
+    items_query: Query<Entity, With<Item>>,
+) {
+    if update_event.iter().count() == 0 {
+        return;
+    }
 
-                    player.item_build.mana = Some((item.id, item.value));
-                    spawn_bag_item(
-                        &mut commands,
-                        &texture_storage,
-                        Transform::from_xyz(4., -6.5, 700.),
-                        &template_storage,
-                        item.id,
-                        false,
-                    )
-                }
-                CardAction::HealthBuff => {
-                    if let Some(val) = player.item_build.health {
-                        if val.1 > item.value {
-                            return;
-                        }
-                    }
+    let player = player_query.single();
+    let interface = interface_query.single();
 
-                    player.item_build.health = Some((item.id, item.value));
-                    spawn_bag_item(
-                        &mut commands,
-                        &texture_storage,
-                        Transform::from_xyz(5., -5.5, 700.),
-                        &template_storage,
-                        item.id,
-                        false,
-                    )
-                }
-                _ => panic!("Illegal template state")
-            };
-            despawn_old_item(&mut commands, item.buff_type.clone(), &items_in_player_query);
-            commands.entity(bag_interface).push_children(&[itm]);
-            commands.entity(entity).despawn_recursive();
+    for entity in items_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 
-            let index = player.items_bag
-                .iter()
-                .position(|i| i.id == item.id)
-                .unwrap();
+    let bag_positions = [-10., -11.5]
+        .into_iter()
+        .flat_map(|y| (0..6).map(move |i| Transform::from_xyz(3. + 1.5 * i as f32, y, 700.)));
 
-            player.items_bag.remove(index);
+    let mut sprites = player.items_bag
+        .iter()
+        .zip(bag_positions)
+        .enumerate()
+        .map(|(index, (i, p))| {
+            let item = spawn_bag_item(&mut commands, &texture_storage, p, &template_storage, i.id, true);
+            commands.entity(item).insert(BagSlot(index));
+            item
+        })
+        .collect::<Vec<Entity>>();
+
+    for (index, slot) in EQUIPMENT_SLOTS.iter().enumerate() {
+        if let Some(item) = player.item_build.slot(index) {
+            let (x, y) = slot.position;
+            let item = spawn_bag_item(
+                &mut commands,
+                &texture_storage,
+                Transform::from_xyz(x, y, 700.),
+                &template_storage,
+                item.id,
+                true,
+            );
+            commands.entity(item).insert(EquippedSlot(index));
+            sprites.push(item);
         }
     }
+
+    commands.entity(interface).push_children(&sprites);
 }
 
-fn despawn_old_item(
-    commands: &mut Commands,
-    buff_type: CardAction,
-    items_in_player: &Query<(&Item, Entity), NonInteractiveItemFilter>,
+pub fn update_set_bonus_text(
+    template_storage: Res<TemplateStorage>,
+    player_query: Query<&Player, Changed<Player>>,
+    mut text_query: Query<&mut Text, With<SetBonusText>>,
 ) {
-    for (item, entity) in items_in_player.iter() {
-        if item.buff_type == buff_type {
-            commands.entity(entity).despawn_recursive();
+    if let Ok(player) = player_query.get_single() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = set_bonus_text(&player.item_build, &template_storage.sets);
         }
     }
 }
 
+fn set_bonus_text(build: &ItemBuild, sets: &[ItemSet]) -> String {
+    build.active_sets(sets)
+        .iter()
+        .map(|set| {
+            let bonus = set.modifiers
+                .iter()
+                .map(|m| format!("+{} {:?}", m.value, m.stat))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("{} {}: {}", set.name, set.pieces, bonus)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 pub fn despawn_bag_interface(
     mut commands: Commands,
     bag_query: Query<Entity, With<BagInterface>>,
@@ -416,22 +429,12 @@ pub fn despawn_bag_interface(
 
 pub fn update_stats(
     mut player_stats_query: Query<&mut CombatStats, With<Player>>,
-    player_query: Query<&Player>
+    player_query: Query<&Player>,
+    template_storage: Res<TemplateStorage>,
 ) {
     let mut stats = player_stats_query.single_mut();
     let player = player_query.single();
-    if let Some(defense) = player.item_build.defense {
-        stats.defense = defense.1 as isize;
-    }
-    if let Some(attack) = player.item_build.attack {
-        stats.attack = attack.1 as isize;
-    }
-    if let Some(mana) = player.item_build.mana {
-        stats.mana = mana.1 as isize;
-    }
-    if let Some(health) = player.item_build.health {
-        stats.max_health += health.1 as isize;
-    }
+    player.derive_stats(&mut stats, &template_storage.sets);
 }
 
 pub fn bag_interface_camera(mut camera_query: Query<&mut Transform, With<Camera>>) {
@@ -544,11 +547,10 @@ pub fn spawn_bag_interface(
     texture_storage: Res<TextureStorage>,
     template_storage: Res<TemplateStorage>,
     player_query: Query<&Player>,
+    mut update_event: EventWriter<UpdateEvent>,
 ) {
     let mut sprites = Vec::with_capacity(5);
-    let player = player_query.single();
-    let items = player.items_bag.clone();
-    let build = &player.item_build;
+    let build = &player_query.single().item_build;
 
     sprites.push(
         spawn_background_element(
@@ -579,93 +581,49 @@ pub fn spawn_bag_interface(
                 scale: Vec3::new(0.01, 0.01, 0.),
                 ..default()
             },
-            "Upgrade your items only when you receive new ones".to_string(),
+            "Click a bag item to wear it, a worn item to take it off".to_string(),
             "Info text".to_string(),
             BagInfoHint,
             PlayerMarker,
         )
     );
 
-    let bag_item_positions = vec![
-        Transform::from_xyz(3.0, -10., 700.),
-        Transform::from_xyz(4.5, -10., 700.),
-        Transform::from_xyz(6., -10., 700.),
-        Transform::from_xyz(7.5, -10., 700.),
-        Transform::from_xyz(9., -10., 700.),
-        Transform::from_xyz(10.5, -10., 700.),
-        Transform::from_xyz(3.0, -11.5, 700.),
-        Transform::from_xyz(4.5, -11.5, 700.),
-        Transform::from_xyz(6., -11.5, 700.),
-        Transform::from_xyz(7.5, -11.5, 700.),
-    ];
-
-    items
-        .iter()
-        .zip(bag_item_positions.iter())
-        .for_each(|(i, p)| {
-            sprites.push(
-                spawn_bag_item(
-                    &mut commands,
-                    &texture_storage,
-                    *p,
-                    &template_storage,
-                    i.id,
-                    true,
-                )
-            )
-        });
-
-    if let Some(def) = build.defense {
-        sprites.push(
-            spawn_bag_item(
-                &mut commands,
-                &texture_storage,
-                Transform::from_xyz(5., -6.5, 700.),
-                &template_storage,
-                def.0,
-                false,
-            )
-        );
-    }
-
-    if let Some(att) = build.attack {
+    for slot in EQUIPMENT_SLOTS.iter() {
+        let (x, y) = slot.position;
         sprites.push(
-            spawn_bag_item(
+            spawn_text(
                 &mut commands,
                 &texture_storage,
-                Transform::from_xyz(6., -6.5, 700.),
-                &template_storage,
-                att.0,
-                false,
+                Transform {
+                    translation: Vec3::new(x, y, 650.),
+                    scale: Vec3::new(0.006, 0.006, 0.),
+                    ..default()
+                },
+                slot.name.to_string(),
+                "Slot label".to_string(),
+                SlotLabel,
+                PlayerMarker,
             )
         );
     }
 
-    if let Some(mana) = build.mana {
-        sprites.push(
-            spawn_bag_item(
-                &mut commands,
-                &texture_storage,
-                Transform::from_xyz(4., -6.5, 700.),
-                &template_storage,
-                mana.0,
-                false,
-            )
-        );
-    }
+    sprites.push(
+        spawn_text(
+            &mut commands,
+            &texture_storage,
+            Transform {
+                translation: Vec3::new(9.5, -6.5, 700.),
+                scale: Vec3::new(0.007, 0.007, 0.),
+                ..default()
+            },
+            set_bonus_text(build, &template_storage.sets),
+            "Set bonus text".to_string(),
+            SetBonusText,
+            PlayerMarker,
+        )
+    );
 
-    if let Some(health) = build.health {
-        sprites.push(
-            spawn_bag_item(
-                &mut commands,
-                &texture_storage,
-                Transform::from_xyz(5., -5.5, 700.),
-                &template_storage,
-                health.0,
-                false,
-            )
-        );
-    }
+    update_event.send(UpdateEvent(true));
 
     let _ = commands
         .spawn()
@@ -803,13 +761,14 @@ pub fn spawn_world_interface(
 pub fn move_dice(
     selected_query: Query<&Selected, With<MoveDice>>,
     mut move_dice_query: Query<&mut MoveDice>,
+    mut rng: ResMut<GameRng>,
 ) {
     let selected = selected_query.single();
     let mut move_dice = move_dice_query.single_mut();
 
     if selected.selected && move_dice.can_roll {
         move_dice.can_roll = false;
-        let roll = thread_rng().gen_range(1..7);
+        let roll = rng.world.gen_range(1..7);
         move_dice.value = roll;
     }
 }
@@ -818,12 +777,14 @@ pub fn next_day_button(
     selected_query: Query<&Selected, With<NextButton>>,
     mut move_dice_query: Query<&mut MoveDice>,
     mut next_button_query: Query<&mut NextButton>,
+    mut new_day: EventWriter<NewDayEvent>,
 ) {
     let selected = selected_query.single();
     let mut move_dice = move_dice_query.single_mut();
     let mut next_button = next_button_query.single_mut();
     if selected.selected && !move_dice.can_roll {
         next_button.increase();
+        new_day.send(NewDayEvent);
         move_dice.can_roll = true;
         move_dice.value = 0_isize;
     }